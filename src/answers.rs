@@ -0,0 +1,163 @@
+use std::{error::Error, fmt::Display, fs, io::ErrorKind, path::Path};
+
+use toml::{Table, Value};
+
+use crate::solver::Answer;
+
+/// Known answers per day and part, stored as TOML:
+///
+/// ```toml
+/// [day01]
+/// part1 = 24000
+/// part2 = 45000
+/// ```
+///
+/// A day can also carry a `skip = "reason"` entry, which the example
+/// regression suite uses for days that can't run their example yet, and a
+/// `settings` table for days whose answers depend on more than the input:
+///
+/// ```toml
+/// [day15.settings]
+/// row = 10
+/// search_size = 20
+/// ```
+#[derive(Debug, Default)]
+pub struct AnswerSheet {
+    table: Table,
+}
+
+/// How an answer compares to the one on the sheet.
+#[derive(Debug, PartialEq)]
+pub enum Verdict {
+    Match,
+    Mismatch(Answer),
+    Unknown,
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Verdict::Match => write!(f, "✔"),
+            Verdict::Mismatch(Answer::Crt(_)) => write!(f, "✘ (doesn't match the recorded screen)"),
+            Verdict::Mismatch(expected) => write!(f, "✘ (expected {})", expected),
+            Verdict::Unknown => write!(f, "?"),
+        }
+    }
+}
+
+impl AnswerSheet {
+    pub fn parse(text: &str) -> Result<Self, toml::de::Error> {
+        Ok(AnswerSheet { table: text.parse()? })
+    }
+
+    /// Loads the sheet at `path`. A missing file is just an empty sheet.
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        match fs::read_to_string(path) {
+            Ok(text) => Ok(Self::parse(&text)?),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(Box::new(e)),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        fs::write(path, self.table.to_string())
+    }
+
+    pub fn get(&self, day: u32, part: u8) -> Option<Answer> {
+        let value = self.day_entry(day)?.get(&format!("part{}", part))?;
+        match value {
+            Value::Integer(x) => Some(Answer::Int(*x)),
+            Value::String(s) if s.contains('\n') => Some(Answer::Crt(s.clone())),
+            Value::String(s) => Some(Answer::Text(s.clone())),
+            _ => None,
+        }
+    }
+
+    /// The settings to solve the day with, if it has any.
+    pub fn settings(&self, day: u32) -> Option<&Table> {
+        self.day_entry(day)?.get("settings")?.as_table()
+    }
+
+    #[cfg(test)]
+    pub fn skip_reason(&self, day: u32) -> Option<&str> {
+        self.day_entry(day)?.get("skip")?.as_str()
+    }
+
+    #[cfg(test)]
+    pub fn has_day(&self, day: u32) -> bool {
+        self.day_entry(day).is_some()
+    }
+
+    pub fn check(&self, day: u32, part: u8, answer: &Answer) -> Verdict {
+        match self.get(day, part) {
+            Some(expected) if expected == *answer => Verdict::Match,
+            Some(expected) => Verdict::Mismatch(expected),
+            None => Verdict::Unknown,
+        }
+    }
+
+    pub fn record(&mut self, day: u32, part: u8, answer: &Answer) {
+        let value = match answer {
+            Answer::Int(x) => Value::Integer(*x),
+            Answer::Text(s) | Answer::Crt(s) => Value::String(s.clone()),
+        };
+
+        let entry = self
+            .table
+            .entry(day_key(day))
+            .or_insert_with(|| Value::Table(Table::new()));
+        if let Value::Table(entry) = entry {
+            entry.insert(format!("part{}", part), value);
+        }
+    }
+
+    fn day_entry(&self, day: u32) -> Option<&Table> {
+        self.table.get(&day_key(day))?.as_table()
+    }
+}
+
+fn day_key(day: u32) -> String {
+    format!("day{:02}", day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checking_answers() {
+        let sheet = AnswerSheet::parse("[day05]\npart1 = \"CMZ\"\n\n[day07]\npart1 = 95437\n").unwrap();
+
+        assert_eq!(sheet.check(5, 1, &"CMZ".into()), Verdict::Match);
+        assert_eq!(sheet.check(7, 1, &95437.into()), Verdict::Match);
+        assert_eq!(sheet.check(7, 1, &42.into()), Verdict::Mismatch(Answer::Int(95437)));
+        assert_eq!(sheet.check(7, 2, &42.into()), Verdict::Unknown);
+        assert_eq!(sheet.check(8, 1, &42.into()), Verdict::Unknown);
+
+        // a number and a text that looks like it are different answers
+        assert_eq!(sheet.check(7, 1, &"95437".into()), Verdict::Mismatch(Answer::Int(95437)));
+    }
+
+    #[test]
+    fn recorded_answers_survive_a_round_trip() {
+        let mut sheet = AnswerSheet::default();
+        let screen = Answer::Crt("#..#\n.##.".to_string());
+
+        sheet.record(1, 1, &24000.into());
+        sheet.record(5, 2, &"MCD".into());
+        sheet.record(10, 2, &screen);
+
+        let sheet = AnswerSheet::parse(&sheet.table.to_string()).unwrap();
+        assert_eq!(sheet.get(1, 1), Some(Answer::Int(24000)));
+        assert_eq!(sheet.get(5, 2), Some(Answer::Text("MCD".to_string())));
+        assert_eq!(sheet.get(10, 2), Some(screen));
+        assert!(sheet.has_day(10));
+        assert!(!sheet.has_day(11));
+    }
+
+    #[test]
+    fn missing_file_is_an_empty_sheet() {
+        let sheet = AnswerSheet::load(Path::new("does/not/exist.toml")).unwrap();
+        assert_eq!(sheet.get(1, 1), None);
+    }
+}
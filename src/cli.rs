@@ -0,0 +1,247 @@
+use std::{
+    num::{NonZeroU32, NonZeroUsize},
+    ops::RangeInclusive,
+    path::PathBuf,
+    thread,
+    time::Duration,
+};
+
+use clap::{Parser, ValueEnum};
+use itertools::Itertools;
+
+use crate::solver::DrawOptions;
+
+/// Days that take a long time unless compiled with `--release`. They only run
+/// when asked for explicitly or with `--all`.
+pub const SLOW_DAYS: [u32; 1] = [16];
+
+/// Runs my Advent of Code 2022 solutions.
+#[derive(Parser, Debug)]
+#[command(about, version)]
+pub struct Cli {
+    /// Run only this day. Can be given more than once.
+    #[arg(short, long, value_parser = clap::value_parser!(u32).range(1..=25))]
+    pub day: Vec<u32>,
+
+    /// Run a selection of days, e.g. `1-14` or `1,3,5-7`.
+    #[arg(long, value_delimiter = ',', value_parser = parse_day_range)]
+    pub days: Vec<RangeInclusive<u32>>,
+
+    /// Run only this part of each day.
+    #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=2))]
+    pub part: Option<u8>,
+
+    /// Run every solved day, including the slow ones.
+    #[arg(short, long, conflicts_with_all = ["day", "days"])]
+    pub all: bool,
+
+    /// Read the puzzle input from this file. Only works for a single day.
+    #[arg(short, long)]
+    pub input: Option<PathBuf>,
+
+    /// Directory with puzzle inputs named `day01.txt`, `day02.txt` and so on.
+    #[arg(long, default_value = "inputs")]
+    pub inputs_dir: PathBuf,
+
+    /// File with the known answers to check against.
+    #[arg(long, default_value = "answers.toml")]
+    pub answers: PathBuf,
+
+    /// Save answers for parts that don't have a known answer yet.
+    #[arg(long)]
+    pub record: bool,
+
+    /// Benchmark mode: solve each day this many times and report the mean,
+    /// minimum and standard deviation of every step.
+    #[arg(long, value_name = "RUNS", value_parser = clap::value_parser!(u32).range(1..))]
+    pub bench: Option<u32>,
+
+    /// How to print the results. `json` and `csv` give one record per day and
+    /// part, for scripts to pick up.
+    #[arg(long, value_enum, default_value_t = Format::Text)]
+    pub format: Format,
+
+    /// Solve up to this many days at the same time, or one per CPU without a
+    /// number. The output is still in order of the days.
+    #[arg(short, long, value_name = "THREADS")]
+    pub jobs: Option<Option<NonZeroUsize>>,
+
+    /// Draw a picture of the puzzle, for the days that can. Without a file it
+    /// goes to the terminal, in colour; with one it's saved as PNG, or PPM for
+    /// file names ending in `.ppm`.
+    #[arg(long, value_name = "FILE")]
+    pub draw: Option<Option<PathBuf>>,
+
+    /// Watch the puzzle being solved in the terminal, for the days that have an
+    /// animation, at this many frames per second (30 without a number).
+    #[arg(long, value_name = "FPS")]
+    pub watch: Option<Option<NonZeroU32>>,
+
+    /// With --draw or --watch, show day 14's cave with the floor of part 2.
+    #[arg(long)]
+    pub floor: bool,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    Json,
+    Csv,
+}
+
+impl Cli {
+    /// The days to run, in order, out of the ones that are `available`.
+    ///
+    /// Without any selection we run everything except the `SLOW_DAYS`.
+    pub fn selected_days(&self, available: &[u32]) -> Vec<u32> {
+        if self.all {
+            return available.to_vec();
+        }
+
+        if self.day.is_empty() && self.days.is_empty() {
+            return available
+                .iter()
+                .filter(|day| !SLOW_DAYS.contains(day))
+                .cloned()
+                .collect();
+        }
+
+        self.day
+            .iter()
+            .cloned()
+            .chain(self.days.iter().cloned().flatten())
+            .sorted()
+            .dedup()
+            .collect()
+    }
+
+    pub fn threads(&self) -> usize {
+        match self.jobs {
+            None => 1,
+            Some(Some(threads)) => threads.get(),
+            Some(None) => thread::available_parallelism().map_or(1, NonZeroUsize::get),
+        }
+    }
+
+    /// How long to show each frame of an animation, if we're watching one.
+    pub fn frame_time(&self) -> Option<Duration> {
+        let fps = self.watch?.map_or(30, NonZeroU32::get);
+        Some(Duration::from_secs(1) / fps)
+    }
+
+    /// What `--draw` and `--watch` show, if we're doing either.
+    pub fn draw_options(&self) -> DrawOptions {
+        DrawOptions { floor: self.floor }
+    }
+
+    pub fn selected_parts(&self) -> Vec<u8> {
+        match self.part {
+            Some(part) => vec![part],
+            None => vec![1, 2],
+        }
+    }
+}
+
+fn parse_day_range(s: &str) -> Result<RangeInclusive<u32>, String> {
+    let parse_day = |day: &str| -> Result<u32, String> {
+        let day: u32 = day
+            .trim()
+            .parse()
+            .map_err(|_| format!("'{}' isn't a day number", day))?;
+        if (1..=25).contains(&day) {
+            Ok(day)
+        } else {
+            Err(format!("Day {} is outside of 1-25", day))
+        }
+    };
+
+    match s.split_once('-') {
+        Some((low, high)) => {
+            let (low, high) = (parse_day(low)?, parse_day(high)?);
+            if low > high {
+                return Err(format!("Range {} is backwards", s));
+            }
+            Ok(low..=high)
+        }
+        None => {
+            let day = parse_day(s)?;
+            Ok(day..=day)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AVAILABLE: [u32; 6] = [1, 2, 12, 13, 15, 16];
+
+    #[test]
+    fn test_parse_day_range() {
+        assert_eq!(parse_day_range("3"), Ok(3..=3));
+        assert_eq!(parse_day_range("1-14"), Ok(1..=14));
+
+        assert!(parse_day_range("14-1").is_err());
+        assert!(parse_day_range("0-3").is_err());
+        assert!(parse_day_range("26").is_err());
+        assert!(parse_day_range("abc").is_err());
+    }
+
+    #[test]
+    fn default_selection_skips_slow_days() {
+        let cli = Cli::parse_from(["aoc_2022"]);
+        assert_eq!(cli.selected_days(&AVAILABLE), vec![1, 2, 12, 13, 15]);
+        assert_eq!(cli.selected_parts(), vec![1, 2]);
+        assert_eq!(cli.format, Format::Text);
+        assert_eq!(cli.threads(), 1);
+
+        let cli = Cli::parse_from(["aoc_2022", "--all", "--format", "json"]);
+        assert_eq!(cli.format, Format::Json);
+        assert_eq!(cli.selected_days(&AVAILABLE), AVAILABLE.to_vec());
+    }
+
+    #[test]
+    fn explicit_selection_combines_days_and_ranges() {
+        let cli = Cli::parse_from(["aoc_2022", "--day", "12", "--days", "1-3,5", "--part", "2"]);
+        assert_eq!(cli.selected_days(&AVAILABLE), vec![1, 2, 3, 5, 12]);
+        assert_eq!(cli.selected_parts(), vec![2]);
+
+        let cli = Cli::parse_from(["aoc_2022", "-d", "3", "-d", "3"]);
+        assert_eq!(cli.selected_days(&AVAILABLE), vec![3]);
+
+        assert!(Cli::try_parse_from(["aoc_2022", "--part", "3"]).is_err());
+        assert!(Cli::try_parse_from(["aoc_2022", "--all", "--day", "3"]).is_err());
+        assert!(Cli::try_parse_from(["aoc_2022", "--bench", "0"]).is_err());
+        assert!(Cli::try_parse_from(["aoc_2022", "--format", "xml"]).is_err());
+        assert!(Cli::try_parse_from(["aoc_2022", "--jobs", "0"]).is_err());
+
+        let cli = Cli::parse_from(["aoc_2022", "--draw", "--day", "12"]);
+        assert_eq!(cli.draw, Some(None));
+        let cli = Cli::parse_from(["aoc_2022", "--draw", "hills.png"]);
+        assert_eq!(cli.draw, Some(Some(PathBuf::from("hills.png"))));
+    }
+
+    #[test]
+    fn jobs_without_a_number_use_every_cpu() {
+        let cli = Cli::parse_from(["aoc_2022", "--jobs", "3"]);
+        assert_eq!(cli.threads(), 3);
+
+        let cli = Cli::parse_from(["aoc_2022", "-j"]);
+        assert!(cli.threads() >= 1);
+        assert_eq!(cli.jobs, Some(None));
+    }
+
+    #[test]
+    fn watching_defaults_to_30_fps() {
+        assert_eq!(Cli::parse_from(["aoc_2022"]).frame_time(), None);
+        let cli = Cli::parse_from(["aoc_2022", "--watch", "--day", "14"]);
+        assert_eq!(cli.frame_time(), Some(Duration::from_secs(1) / 30));
+        let cli = Cli::parse_from(["aoc_2022", "--watch", "100"]);
+        assert_eq!(cli.frame_time(), Some(Duration::from_millis(10)));
+        assert!(Cli::try_parse_from(["aoc_2022", "--watch", "0"]).is_err());
+
+        let cli = Cli::parse_from(["aoc_2022", "--watch", "--floor", "--day", "14"]);
+        assert_eq!(cli.draw_options(), DrawOptions { floor: true });
+        assert_eq!(Cli::parse_from(["aoc_2022", "--draw"]).draw_options(), DrawOptions::default());
+    }
+}
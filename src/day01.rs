@@ -1,20 +1,40 @@
-pub fn run_day_01(input: String) {
-    let blocks = input.trim().split("\n\n");
-    let sums = blocks.map(handle_block);
-
-    let (max_sum, next_sum, next_next_sum) = get_top_three_calories(sums);
-    println!("The elf with the most calories has {} calories.", max_sum);
-    println!(
-        "The top three elves have between them {} calories.",
-        max_sum + next_sum + next_next_sum
-    );
+use simple_error::SimpleError;
+
+use crate::error::{parse_lines, ParseError};
+use crate::solver::{Answer, Puzzle};
+
+pub struct Day01;
+
+impl Puzzle for Day01 {
+    /// The calories carried by each elf.
+    type Parsed = Vec<i32>;
+
+    fn day(&self) -> u32 {
+        1
+    }
+
+    fn title(&self) -> &'static str {
+        "Calorie Counting"
+    }
+
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Parsed, ParseError<'a>> {
+        input.trim().split("\n\n").map(handle_block).collect()
+    }
+
+    fn part1(&self, sums: &Self::Parsed) -> Result<Answer, SimpleError> {
+        let (max_sum, _, _) = get_top_three_calories(sums.iter().cloned());
+        Ok(max_sum.into())
+    }
+
+    fn part2(&self, sums: &Self::Parsed) -> Result<Answer, SimpleError> {
+        let (max_sum, next_sum, next_next_sum) = get_top_three_calories(sums.iter().cloned());
+        Ok((max_sum + next_sum + next_next_sum).into())
+    }
 }
 
-fn handle_block(block: &str) -> i32 {
-    block
-        .split('\n')
-        .map(|num_as_str| num_as_str.parse::<i32>().unwrap())
-        .sum()
+fn handle_block(block: &str) -> Result<i32, ParseError<'_>> {
+    let calories = parse_lines(block, |num_as_str| num_as_str.parse::<i32>())?;
+    Ok(calories.into_iter().sum())
 }
 
 fn get_top_three_calories<I: Iterator<Item = i32>>(sums: I) -> (i32, i32, i32) {
@@ -42,7 +62,7 @@ mod tests {
         let split_on_blanks = input.split("\n\n");
         assert_eq!(3, split_on_blanks.clone().count());
 
-        let sum_iterator = split_on_blanks.map(handle_block);
+        let sum_iterator = split_on_blanks.map(|block| handle_block(block).unwrap());
         assert_eq!(5, sum_iterator.max().unwrap());
     }
 
@@ -51,7 +71,7 @@ mod tests {
         let input = "1\n\n2\n3\n\n4\n5\n\n6\n7".trim().to_string();
 
         let blocks = input.split("\n\n");
-        let sum_iterator = blocks.map(handle_block);
+        let sum_iterator = blocks.map(|block| handle_block(block).unwrap());
 
         // let mut heap: BinaryHeap<_> = sum_iterator.collect();
         // // Top 2 items are 13 (6 + 7) and 9 (4 + 5).
@@ -72,4 +92,10 @@ mod tests {
         });
         assert_eq!((13, 9, 5), top_three_sums);
     }
+
+    #[test]
+    fn bad_calories_point_at_their_line() {
+        let error = handle_block("1000\n2OOO\n3000").unwrap_err();
+        assert_eq!(error.at, "2OOO");
+    }
 }
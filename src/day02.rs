@@ -1,54 +1,80 @@
 use itertools::Itertools;
 
 use simple_error::SimpleError;
-pub fn run_day_02(input: String) {
-    let rounds = parse_input(input.clone()).unwrap();
 
-    let total_score = score_game(rounds.into_iter());
-    println!("Total score following initial startegy is: {}", total_score);
+use crate::error::ParseError;
+use crate::solver::{Answer, Puzzle};
 
-    let part2_input = parse_part2_input(input).unwrap();
-    let part2_rounds = compute_required_hand_shapes(part2_input);
+pub struct Day02;
 
-    let total_score_round2 = score_game(part2_rounds);
-    println!(
-        "With the renewed strategy, the total score is: {}",
-        total_score_round2
-    );
+impl Puzzle for Day02 {
+    /// The strategy guide, read the way each part understands it.
+    type Parsed = (Vec<(HandShape, HandShape)>, Vec<(HandShape, GameOutcome)>);
+
+    fn day(&self) -> u32 {
+        2
+    }
+
+    fn title(&self) -> &'static str {
+        "Rock Paper Scissors"
+    }
+
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Parsed, ParseError<'a>> {
+        let rounds = parse_input(input)?;
+        let part2_input = parse_part2_input(input)?;
+        Ok((rounds, part2_input))
+    }
+
+    fn part1(&self, (rounds, _): &Self::Parsed) -> Result<Answer, SimpleError> {
+        let total_score = score_game(rounds.iter().cloned());
+        Ok(total_score.into())
+    }
+
+    fn part2(&self, (_, part2_input): &Self::Parsed) -> Result<Answer, SimpleError> {
+        let part2_rounds = compute_required_hand_shapes(part2_input);
+
+        let total_score_round2 = score_game(part2_rounds);
+        Ok(total_score_round2.into())
+    }
 }
 
 fn compute_required_hand_shapes(
-    part2_input: Vec<(HandShape, GameOutcome)>,
-) -> impl Iterator<Item = (HandShape, HandShape)> {
+    part2_input: &[(HandShape, GameOutcome)],
+) -> impl Iterator<Item = (HandShape, HandShape)> + '_ {
     part2_input
-        .into_iter()
-        .map(|(shape, outcome)| (shape, hand_shape_for_outcome(&shape, &outcome)))
+        .iter()
+        .map(|(shape, outcome)| (*shape, hand_shape_for_outcome(shape, outcome)))
 }
 
-fn parse_input(input: String) -> Result<Vec<(HandShape, HandShape)>, Box<dyn Error>> {
+fn parse_input(input: &str) -> Result<Vec<(HandShape, HandShape)>, ParseError<'_>> {
     input.lines().map(parse_line).collect()
 }
 
-fn parse_part2_input(input: String) -> Result<Vec<(HandShape, GameOutcome)>, Box<dyn Error>> {
+fn parse_part2_input(input: &str) -> Result<Vec<(HandShape, GameOutcome)>, ParseError<'_>> {
     input.lines().map(parse_part2_line).collect()
 }
 
-fn parse_line(input: &str) -> Result<(HandShape, HandShape), Box<dyn Error>> {
-    let res_vec: Result<Vec<HandShape>, _> = input.split(' ').map(|entry| entry.parse()).collect();
-    res_vec?
-        .into_iter()
-        .collect_tuple()
-        .ok_or(Box::new(SimpleError::new(
-            "Wrong number of hand shapes on that line",
-        )))
+fn parse_line(input: &str) -> Result<(HandShape, HandShape), ParseError<'_>> {
+    let (opponent, own) = split_codes(input)?;
+    Ok((
+        opponent.parse().map_err(|e| ParseError::new(opponent, e))?,
+        own.parse().map_err(|e| ParseError::new(own, e))?,
+    ))
 }
 
-fn parse_part2_line(input: &str) -> Result<(HandShape, GameOutcome), Box<dyn Error>> {
-    let codes: (&str, &str) = input
+fn parse_part2_line(input: &str) -> Result<(HandShape, GameOutcome), ParseError<'_>> {
+    let (opponent, outcome) = split_codes(input)?;
+    Ok((
+        opponent.parse().map_err(|e| ParseError::new(opponent, e))?,
+        outcome.parse().map_err(|e| ParseError::new(outcome, e))?,
+    ))
+}
+
+fn split_codes(input: &str) -> Result<(&str, &str), ParseError<'_>> {
+    input
         .split(' ')
         .collect_tuple()
-        .ok_or(Box::new(SimpleError::new("Wrong format for line")))?;
-    Ok((codes.0.parse()?, codes.1.parse()?))
+        .ok_or_else(|| ParseError::new(input, "Expected two codes separated by a space"))
 }
 
 fn score_game(rounds: impl Iterator<Item = (HandShape, HandShape)>) -> u64 {
@@ -208,7 +234,11 @@ mod tests {
     fn hacking_around_with_parsing_a_tuple() {
         let two_shapes: (HandShape, HandShape) = parse_line("A X").unwrap();
 
-        assert_eq!(two_shapes.0, HandShape::Rock)
+        assert_eq!(two_shapes.0, HandShape::Rock);
+
+        let line = "A Q";
+        assert_eq!(parse_line(line).unwrap_err().at.as_ptr(), line[2..].as_ptr());
+        assert!(parse_line("A X Y").is_err());
     }
 
     #[test]
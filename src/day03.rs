@@ -2,28 +2,66 @@ use itertools::Itertools;
 use simple_error::SimpleError;
 use std::collections::HashSet;
 
-pub fn run_day_03(input: String) {
-    let total_score: u64 = input
-        .lines()
-        .map(|line| find_item_that_appears_in_both(line).unwrap())
-        .map(|item| score_item(item).unwrap())
-        .sum();
-
-    println!("Total score of the mixed-up items is {}", total_score);
-
-    let total_score_2: u64 = input
-        .lines()
-        .tuples()
-        .map(|(a, b, c)| [a, b, c])
-        .map(|rucksacks| find_shared_item_in_three_group(&rucksacks).unwrap())
-        .map(|item| score_item(item).unwrap())
-        .sum();
-
-    println!("Total score of the bages is {}", total_score_2);
+use crate::error::ParseError;
+use crate::solver::{Answer, Puzzle};
+
+pub struct Day03;
+
+impl Puzzle for Day03 {
+    /// The items in each rucksack.
+    type Parsed = Vec<String>;
+
+    fn day(&self) -> u32 {
+        3
+    }
+
+    fn title(&self) -> &'static str {
+        "Rucksack Reorganization"
+    }
+
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Parsed, ParseError<'a>> {
+        input.lines().map(|line| parse_rucksack(line).map(str::to_string)).collect()
+    }
+
+    fn part1(&self, rucksacks: &Self::Parsed) -> Result<Answer, SimpleError> {
+        let total_score: u64 = rucksacks
+            .iter()
+            .map(|line| find_item_that_appears_in_both(line).and_then(score_item))
+            .sum::<Result<_, _>>()?;
+
+        Ok(total_score.into())
+    }
+
+    fn part2(&self, rucksacks: &Self::Parsed) -> Result<Answer, SimpleError> {
+        if !rucksacks.len().is_multiple_of(3) {
+            return Err(SimpleError::new("The elves don't split up into groups of three"));
+        }
+
+        let total_score_2: u64 = rucksacks
+            .iter()
+            .map(String::as_str)
+            .tuples()
+            .map(|(a, b, c)| [a, b, c])
+            .map(|rucksacks| find_shared_item_in_three_group(&rucksacks).and_then(score_item))
+            .sum::<Result<_, _>>()?;
+
+        Ok(total_score_2.into())
+    }
+}
+
+/// Checks that a rucksack has only items we can score, split evenly between its compartments.
+fn parse_rucksack(line: &str) -> Result<&str, ParseError<'_>> {
+    if let Some((pos, item)) = line.char_indices().find(|(_, c)| !c.is_ascii_alphabetic()) {
+        return Err(ParseError::new(&line[pos..], format!("'{}' isn't an item", item)));
+    }
+    if !line.len().is_multiple_of(2) {
+        return Err(ParseError::new(line, "Uneven number of items, can't split into two compartments"));
+    }
+    Ok(line)
 }
 
 fn find_item_that_appears_in_both(input: &str) -> Result<char, SimpleError> {
-    if input.len() % 2 != 0 {
+    if !input.len().is_multiple_of(2) {
         return Err(SimpleError::new("Uneven length for input string!"));
     }
 
@@ -34,12 +72,12 @@ fn find_item_that_appears_in_both(input: &str) -> Result<char, SimpleError> {
     let shared_items: Vec<char> = first_items.intersection(&second_items).cloned().collect();
 
     if shared_items.len() != 1 {
-        return Err(SimpleError::new(format!(
+        Err(SimpleError::new(format!(
             "The two backpacks share more than one item: {:?}",
             shared_items
-        )));
+        )))
     } else {
-        return Ok(shared_items[0]);
+        Ok(shared_items[0])
     }
 }
 
@@ -104,4 +142,11 @@ mod tests {
         let input: [&str; 3] = ["abc", "acd", "adf"];
         assert_eq!(find_shared_item_in_three_group(&input), Ok('a'));
     }
+
+    #[test]
+    fn test_parse_rucksack() {
+        assert_eq!(parse_rucksack("vJrwpWtwJgWr"), Ok("vJrwpWtwJgWr"));
+        assert_eq!(parse_rucksack("vJr wpW").unwrap_err().at, " wpW");
+        assert_eq!(parse_rucksack("vJr").unwrap_err().at, "vJr");
+    }
 }
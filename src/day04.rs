@@ -1,142 +1,131 @@
-use std::error::Error;
-
-use range::Range;
+use itertools::Itertools;
 use simple_error::SimpleError;
 
-pub fn run_day_04(input: String) {
-    let ranges_iter = input.lines().map(|line| parse_line(line).unwrap());
-
-    let count = ranges_iter.map(|ranges| one_contains_other(ranges))
-        .filter(|contains| *contains == true)
-        .count();
-
-    println!("There's {} pairs of elves whose areas are contained within each other.", count);
+use crate::error::ParseError;
+use crate::interval::Interval;
+use crate::solver::{Answer, Puzzle};
 
-    let ranges_iter = input.lines().map(|line| parse_line(line).unwrap());
-    let count_2 = ranges_iter.map(|ranges| ranges.0.overlaps(&ranges.1))
-    .filter(|overlaps| *overlaps == true)
-    .count();
+pub struct Day04;
 
-    println!("There's {} pairs of elves whose areas overlap at all.", count_2);
-}
+impl Puzzle for Day04 {
+    /// The pair of section ranges on each line.
+    type Parsed = Vec<(Interval, Interval)>;
 
-fn one_contains_other(ranges: (Range, Range)) -> bool {
-    ranges.0.contains(&ranges.1) || ranges.1.contains(&ranges.0)
-}
+    fn day(&self) -> u32 {
+        4
+    }
 
-fn parse_line(line: &str) -> Result<(Range, Range), Box<dyn Error>> {
-    let parts: Vec<&str> = line.split(',').collect();
-    if parts.len() != 2 {
-        return Err(Box::new(SimpleError::new(
-            "Invalid input; doesn't have two comma-separated parts",
-        )));
+    fn title(&self) -> &'static str {
+        "Camp Cleanup"
     }
 
-    let ranges: Vec<Range> = parts
-        .into_iter()
-        .map(|part| part.parse())
-        .collect::<Result<Vec<Range>, _>>()?;
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Parsed, ParseError<'a>> {
+        input.lines().map(parse_line).collect()
+    }
 
-    Ok((ranges[0], ranges[1]))
-}
+    fn part1(&self, pairs: &Self::Parsed) -> Result<Answer, SimpleError> {
+        let count = pairs.iter().cloned().map(one_contains_other)
+            .filter(|contains| *contains)
+            .count();
 
-mod range {
-    use std::str::FromStr;
+        Ok(count.into())
+    }
 
-    use simple_error::SimpleError;
+    fn part2(&self, pairs: &Self::Parsed) -> Result<Answer, SimpleError> {
+        let count_2 = pairs.iter().map(|ranges| ranges.0.overlaps(&ranges.1))
+        .filter(|overlaps| *overlaps)
+        .count();
 
-    #[derive(Debug, PartialEq, Clone, Copy)]
-    pub struct Range {
-        low: u64,
-        high: u64,
+        Ok(count_2.into())
     }
+}
 
-    impl Range {
-        pub fn new(low: u64, high: u64) -> Result<Range, SimpleError> {
-            if low <= high {
-                Ok(Range { low, high })
-            } else {
-                Err(SimpleError::new("low must be less than or equal high"))
-            }
-        }
-
-        pub fn contains(&self, other: &Range) -> bool {
-            other.low >= self.low && other.high <= self.high
-        }
-
-        pub fn overlaps(&self, other: &Range) -> bool {
-            !self.disjoint(&other)
-        }
-
-        pub fn disjoint(&self, other: &Range) -> bool {
-            other.low > self.high || other.high < self.low
-        }
-    }
+fn one_contains_other(ranges: (Interval, Interval)) -> bool {
+    ranges.0.contains_interval(&ranges.1) || ranges.1.contains_interval(&ranges.0)
+}
 
-    impl FromStr for Range {
-        type Err = SimpleError;
+fn parse_line(line: &str) -> Result<(Interval, Interval), ParseError<'_>> {
+    let (first, second) = line.split(',').collect_tuple().ok_or_else(|| ParseError::new(
+        line,
+        "Invalid input; doesn't have two comma-separated parts",
+    ))?;
 
-        fn from_str(s: &str) -> Result<Self, Self::Err> {
-            let parts: Vec<&str> = s.split('-').collect();
-            if parts.len() != 2 {
-                return Err(SimpleError::new("Incorrect format. Needs to be 'low-high'"));
-            }
+    Ok((parse_range(first)?, parse_range(second)?))
+}
 
-            let low_high: Result<Vec<u64>, _> =
-                parts.into_iter().map(|s| s.parse::<u64>()).collect();
-            if let Err(error) = low_high {
-                return Err(SimpleError::from(error));
-            }
-            let low_high = low_high.unwrap();
+fn parse_range(part: &str) -> Result<Interval, ParseError<'_>> {
+    parse_sections(part).map_err(|e| ParseError::new(part, e))
+}
 
-            return Self::new(low_high[0], low_high[1]);
-        }
+/// Sections `low-high`, where `low` can't be more than `high`.
+fn parse_sections(s: &str) -> Result<Interval, SimpleError> {
+    let (low, high) = s
+        .split('-')
+        .collect_tuple()
+        .ok_or_else(|| SimpleError::new("Incorrect format. Needs to be 'low-high'"))?;
+
+    let low: u64 = low.parse().map_err(SimpleError::from)?;
+    let high: u64 = high.parse().map_err(SimpleError::from)?;
+    if low > high {
+        return Err(SimpleError::new("low must be less than or equal high"));
     }
+
+    Ok(Interval::new(low as i64, high as i64))
 }
 
 #[cfg(test)]
 mod tests {
-    use super::range::Range;
+    use super::{parse_line, parse_sections};
+    use crate::interval::Interval;
 
     #[test]
     fn test_if_ranges_contain_each_other() {
-        let range = Range::new(5, 10).unwrap();
+        let range = Interval::new(5, 10);
 
-        let contained_range = Range::new(7, 8).unwrap();
-        assert!(range.contains(&contained_range));
+        let contained_range = Interval::new(7, 8);
+        assert!(range.contains_interval(&contained_range));
 
-        assert!(!contained_range.contains(&range));
+        assert!(!contained_range.contains_interval(&range));
 
-        let overlap_range = Range::new(4, 9).unwrap();
-        assert!(!range.contains(&overlap_range));
-        assert!(!overlap_range.contains(&range));
+        let overlap_range = Interval::new(4, 9);
+        assert!(!range.contains_interval(&overlap_range));
+        assert!(!overlap_range.contains_interval(&range));
     }
 
     #[test]
     fn test_some_string_parsing() {
-        let range: Range = "5-10".parse().unwrap();
-        let expected = Range::new(5, 10).unwrap();
+        let range = parse_sections("5-10").unwrap();
+        let expected = Interval::new(5, 10);
 
         assert_eq!(range, expected);
 
-        let should_error: Result<Range, _> = "abc-10".parse();
+        let should_error = parse_sections("abc-10");
         assert!(should_error.is_err(), "Parsing abc-10 should be an error");
 
-        let should_error: Result<Range, _> = "42-10".parse();
+        let should_error = parse_sections("42-10");
         assert!(should_error.is_err(), "Parsing 42-10 should be an error");
     }
 
     #[test]
     fn test_overlaps() {
-        let range = Range::new(2, 4).unwrap();
-        let other = Range::new(5, 6).unwrap();
+        let range = Interval::new(2, 4);
+        let other = Interval::new(5, 6);
         
         assert!(!range.overlaps(&other));
 
-        let other = Range::new(1, 2).unwrap();
+        let other = Interval::new(1, 2);
         assert!(range.overlaps(&other));
 
-        let other = Range::new(4, 10).unwrap();
+        let other = Interval::new(4, 10);
         assert!(range.overlaps(&other));
     }
+
+    #[test]
+    fn parse_errors_point_at_the_bad_range() {
+        assert!(parse_line("2-4,6-8").is_ok());
+
+        let line = "2-4,8-6";
+        assert_eq!(parse_line(line).unwrap_err().at.as_ptr(), line[4..].as_ptr());
+        assert_eq!(parse_line("2-4").unwrap_err().at, "2-4");
+    }
 }
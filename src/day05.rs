@@ -1,40 +1,73 @@
 use lazy_regex::regex;
 use simple_error::SimpleError;
-use std::error::Error;
 
 use self::crate_stacks::CrateStacks;
+use crate::error::ParseError;
+use crate::solver::{Answer, Puzzle};
 
-pub fn run_day_05(input: String) {
-    let mut blocks = input.split("\n\n");
-    let crate_block = blocks.next().unwrap();
-    let move_block = blocks.next().unwrap();
+pub struct Day05;
 
-    let mut crate_stacks = parse_crate_block(crate_block);
+impl Puzzle for Day05 {
+    /// The starting stacks and the moves of the crane.
+    type Parsed = (CrateStacks, Vec<Move>);
 
-    for move_line in move_block.lines() {
-        let m = parse_move_line(move_line).unwrap();
-        crate_stacks.apply_move(m)
+    fn day(&self) -> u32 {
+        5
     }
 
-    let top_items: String = crate_stacks.top_items().collect();
+    fn title(&self) -> &'static str {
+        "Supply Stacks"
+    }
+
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Parsed, ParseError<'a>> {
+        let (crate_block, move_block) = split_blocks(input)?;
+
+        let crate_stacks = parse_crate_block(crate_block)?;
+        let moves = move_block
+            .lines()
+            .map(|move_line| parse_move_line(move_line, crate_stacks.len()))
+            .collect::<Result<_, _>>()?;
+
+        Ok((crate_stacks, moves))
+    }
+
+    fn part1(&self, (crate_stacks, moves): &Self::Parsed) -> Result<Answer, SimpleError> {
+        let mut crate_stacks = crate_stacks.clone();
 
-    println!("After all this moving, the top items on the crates are {}", top_items);
+        for m in moves {
+            crate_stacks.apply_move(*m)?
+        }
 
-    let mut crate_stacks = parse_crate_block(crate_block);
+        let top_items: String = crate_stacks.top_items().collect();
 
-    for move_line in move_block.lines() {
-        let m = parse_move_line(move_line).unwrap();
-        crate_stacks.apply_move_2(m)
+        Ok(top_items.into())
     }
 
-    let top_items: String = crate_stacks.top_items().collect();
+    fn part2(&self, (crate_stacks, moves): &Self::Parsed) -> Result<Answer, SimpleError> {
+        let mut crate_stacks = crate_stacks.clone();
+
+        for m in moves {
+            crate_stacks.apply_move_2(*m)?
+        }
+
+        let top_items: String = crate_stacks.top_items().collect();
+
+        Ok(top_items.into())
+    }
+}
 
-    println!("After all this moving with the other device, the top items on the crates are {}", top_items);
+fn split_blocks(input: &str) -> Result<(&str, &str), ParseError<'_>> {
+    input.split_once("\n\n").ok_or_else(|| ParseError::end_of_input(
+        "Expected an empty line between the crates and the moves",
+    ))
 }
 
-fn parse_crate_block(crate_block: &str) -> CrateStacks {
+fn parse_crate_block(crate_block: &str) -> Result<CrateStacks, ParseError<'_>> {
     //First, figure out number of crate blocks:
-    let num_stacks: usize = (crate_block.lines().next().unwrap().len() + 1) / 4;
+    let num_stacks: usize = (crate_block.lines().map(str::len).max().unwrap_or(0) + 1) / 4;
+    if num_stacks == 0 {
+        return Err(ParseError::new(crate_block, "Expected a drawing of the crate stacks"));
+    }
     let mut crate_stacks = CrateStacks::new(num_stacks);
 
     for line in crate_block.lines() {
@@ -44,14 +77,17 @@ fn parse_crate_block(crate_block: &str) -> CrateStacks {
         }
     }
 
-    crate_stacks
+    Ok(crate_stacks)
 }
 
 mod crate_stacks {
     use std::collections::VecDeque;
 
+    use simple_error::SimpleError;
+
     use super::Move;
 
+    #[derive(Clone)]
     pub struct CrateStacks {
         stacks: Vec<VecDeque<char>>,
     }
@@ -67,21 +103,33 @@ mod crate_stacks {
             self.stacks[stack-1].push_front(crate_id);
         }
 
-        pub fn apply_move(&mut self, m: Move) {
+        pub fn len(&self) -> usize {
+            self.stacks.len()
+        }
+
+        pub fn apply_move(&mut self, m: Move) -> Result<(), SimpleError> {
             for _ in 0..m.num {
-                let removed_item = self.stacks[m.from-1].pop_back().unwrap();
+                let removed_item = self.pop(m)?;
                 self.stacks[m.to-1].push_back(removed_item);
             }
+            Ok(())
         }
 
-        pub fn apply_move_2(&mut self, m: Move) {
+        pub fn apply_move_2(&mut self, m: Move) -> Result<(), SimpleError> {
             let mut removed_items = Vec::new();
             for _ in 0..m.num {
-                removed_items.push(self.stacks[m.from-1].pop_back().unwrap());
+                removed_items.push(self.pop(m)?);
             }
             for _ in 0..m.num {
                 self.stacks[m.to-1].push_back(removed_items.pop().unwrap());
             }
+            Ok(())
+        }
+
+        fn pop(&mut self, m: Move) -> Result<char, SimpleError> {
+            self.stacks[m.from-1].pop_back().ok_or_else(|| {
+                SimpleError::new(format!("Can't move {} crates off stack {}, it runs out", m.num, m.from))
+            })
         }
 
         pub fn top_items(&self) -> impl Iterator<Item = &char> {
@@ -99,17 +147,25 @@ pub struct Move {
     to: usize,
 }
 
-fn parse_move_line(line: &str) -> Result<Move, Box<dyn Error>> {
-    let re = regex!(r"^move (\d+) from (\d+) to (\d)$");
-    let caps = re.captures(line).ok_or(Box::new(SimpleError::new(
+fn parse_move_line(line: &str, num_stacks: usize) -> Result<Move, ParseError<'_>> {
+    let re = regex!(r"^move (\d+) from (\d+) to (\d+)$");
+    let caps = re.captures(line).ok_or_else(|| ParseError::new(
+        line,
         "Line doesn't match expectation for move",
-    )))?;
-
-    let num: usize = caps.get(1).unwrap().as_str().parse().unwrap(); // If the expreccsion matched, then it should work here without panic
-    let from: usize = caps.get(2).unwrap().as_str().parse().unwrap();
-    let to: usize = caps.get(3).unwrap().as_str().parse().unwrap();
+    ))?;
+
+    // If the expression matched, these are all digits, but they could still be out of range
+    let number_at = |i: usize| -> Result<usize, ParseError> {
+        let m = caps.get(i).unwrap();
+        let at = &line[m.start()..];
+        let number: usize = m.as_str().parse().map_err(|e| ParseError::new(at, e))?;
+        if i > 1 && !(1..=num_stacks).contains(&number) {
+            return Err(ParseError::new(at, format!("There's no stack {}", number)));
+        }
+        Ok(number)
+    };
 
-    Ok(Move { num, from, to })
+    Ok(Move { num: number_at(1)?, from: number_at(2)?, to: number_at(3)? })
 }
 
 fn parse_crate_line(line: &str) -> Vec<(usize, char)> {
@@ -119,12 +175,9 @@ fn parse_crate_line(line: &str) -> Vec<(usize, char)> {
 
     for stack in 1..num_stacks + 1 {
         let stack_pos = (stack - 1) * 4 + 1;
-        let char_at = chars[stack_pos as usize];
-        match char_at {
-            'A'..='Z' => {
-                stack_crate.push((stack, char_at));
-            }
-            _ => (),
+        // lines might be missing the trailing spaces of empty stacks
+        if let Some(&char_at @ 'A'..='Z') = chars.get(stack_pos) {
+            stack_crate.push((stack, char_at));
         }
     }
 
@@ -154,7 +207,7 @@ mod tests {
     #[test]
     fn test_parsing_move_line() {
         let line = "move 11 from 8 to 3";
-        let my_move = parse_move_line(line).unwrap();
+        let my_move = parse_move_line(line, 9).unwrap();
         assert_eq!(
             my_move,
             Move {
@@ -163,6 +216,9 @@ mod tests {
                 from: 8
             }
         );
+
+        assert_eq!(parse_move_line(line, 5).unwrap_err().at, "8 to 3");
+        assert_eq!(parse_move_line("move 11 from 8", 9).unwrap_err().at, "move 11 from 8");
     }
 
     #[test]
@@ -186,25 +242,27 @@ mod tests {
         assert_eq!(top_items, "D  ".to_string());
 
         let m = Move{num: 1, from: 1, to: 3};
-        stack.apply_move(m);
+        stack.apply_move(m).unwrap();
         let top_items: String = stack.top_items().collect();
         assert_eq!(top_items, "  D".to_string());
     }
 
     #[test]
     fn test_parse_crate_block() {
-        let crate_stack = parse_crate_block("[A] [B]    \n[C] [D] [E]");
+        let crate_stack = parse_crate_block("[A] [B]\n[C] [D] [E]").unwrap();
         let top_items: String = crate_stack.top_items().collect();
         assert_eq!(top_items, "ABE".to_string());
     }
 
     #[test]
     fn test_apply_move_2() {
-        let mut crate_stack = parse_crate_block("[A] [B]    \n[C] [D] [E]");
+        let mut crate_stack = parse_crate_block("[A] [B]    \n[C] [D] [E]").unwrap();
         let m = Move{num: 2, from: 1, to: 3};
-        crate_stack.apply_move_2(m);
+        crate_stack.apply_move_2(m).unwrap();
 
         let top_items: String = crate_stack.top_items().collect();
         assert_eq!(top_items, " BA".to_string());
+
+        assert!(crate_stack.apply_move_2(m).is_err());
     }
 }
@@ -1,18 +1,40 @@
 use crate::device::{find_start_of_packet_marker, find_start_of_message_marker};
+use simple_error::SimpleError;
 
-pub fn run_day_06(input: String) {
-    let pos = find_start_of_packet_marker(input.chars());
-    if pos.is_none() {
-        panic!{"Couldn't find any start of packet marker, yo! 🚨"}
+use crate::error::ParseError;
+use crate::solver::{Answer, Puzzle};
+
+pub struct Day06;
+
+impl Puzzle for Day06 {
+    /// The datastream buffer.
+    type Parsed = Vec<char>;
+
+    fn day(&self) -> u32 {
+        6
+    }
+
+    fn title(&self) -> &'static str {
+        "Tuning Trouble"
+    }
+
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Parsed, ParseError<'a>> {
+        let stream = input.trim_end();
+        if let Some((pos, c)) = stream.char_indices().find(|(_, c)| !c.is_ascii_lowercase()) {
+            return Err(ParseError::new(&stream[pos..], format!("'{}' doesn't belong in the datastream", c)));
+        }
+        Ok(stream.chars().collect())
     }
-    let pos = pos.unwrap();
-    println!("The start-of-packet marker is at position {}", pos);
 
-    let pos = find_start_of_message_marker(input.chars());
-    if pos.is_none() {
-        panic!{"Couldn't find any start of message marker, yo! 🚨"}
+    fn part1(&self, stream: &Self::Parsed) -> Result<Answer, SimpleError> {
+        let pos = find_start_of_packet_marker(stream.iter().cloned())
+            .ok_or_else(|| SimpleError::new("Couldn't find any start of packet marker, yo! 🚨"))?;
+        Ok(pos.into())
     }
-    let pos = pos.unwrap();
-    println!("The start-of-message marker is at position {}", pos);
 
-}
\ No newline at end of file
+    fn part2(&self, stream: &Self::Parsed) -> Result<Answer, SimpleError> {
+        let pos = find_start_of_message_marker(stream.iter().cloned())
+            .ok_or_else(|| SimpleError::new("Couldn't find any start of message marker, yo! 🚨"))?;
+        Ok(pos.into())
+    }
+}
@@ -1,21 +1,46 @@
+use std::collections::HashMap;
+
 use crate::device::parse_terminal_output_for_dir_sizes;
+use simple_error::SimpleError;
+
+use crate::error::ParseError;
+use crate::solver::{Answer, Puzzle};
+
+pub struct Day07;
+
+impl Puzzle for Day07 {
+    /// The total size of each directory, by path.
+    type Parsed = HashMap<String, u64>;
+
+    fn day(&self) -> u32 {
+        7
+    }
+
+    fn title(&self) -> &'static str {
+        "No Space Left On Device"
+    }
 
-pub fn run_day_07(input: String) {
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Parsed, ParseError<'a>> {
+        parse_terminal_output_for_dir_sizes(input)
+    }
 
-    let dir_sizes = parse_terminal_output_for_dir_sizes(&input);
+    fn part1(&self, dir_sizes: &Self::Parsed) -> Result<Answer, SimpleError> {
+        let total_sum: u64 = dir_sizes.values().filter(|size| **size <= 100000).sum();
 
-    let total_sum: u64 = dir_sizes.values().filter(|size| **size <= 100000).sum();
+        Ok(total_sum.into())
+    }
 
-    println!("Total sum of directories at most 100000 is {}", total_sum);
+    fn part2(&self, dir_sizes: &Self::Parsed) -> Result<Answer, SimpleError> {
+        const TOTAL_SPACE: u64 =  70000000;
+        const SPACE_NEEDED: u64 = 30000000;
 
-    const TOTAL_SPACE: u64 =  70000000;
-    const SPACE_NEEDED: u64 = 30000000;
+        let currently_occupied: u64  = *dir_sizes.get("/").ok_or_else(|| SimpleError::new("There are no files at all"))?;
+        let unused_space = TOTAL_SPACE.checked_sub(currently_occupied)
+            .ok_or_else(|| SimpleError::new("The files don't fit on the disk"))?;
 
-    let currently_occupied: u64  = *dir_sizes.get("/").unwrap();
-    let unused_space = TOTAL_SPACE - currently_occupied;
-    
-    let need_to_free_at_least = SPACE_NEEDED - unused_space; 
+        let need_to_free_at_least = SPACE_NEEDED.saturating_sub(unused_space);
 
-    let dir_to_delete = dir_sizes.values().filter(|size| **size >= need_to_free_at_least).min().unwrap();
-    println!("Size of the dir that we should delete is {}", dir_to_delete);
+        let dir_to_delete = dir_sizes.values().filter(|size| **size >= need_to_free_at_least).min().unwrap();
+        Ok((*dir_to_delete).into())
+    }
 }
@@ -1,43 +1,54 @@
 use ndarray::prelude::*;
 use ndarray::Zip;
 
-pub fn run_day_08(input: String) {
-    let trees = digit_matrix_to_array(&input);
+use simple_error::SimpleError;
 
-    let ans = count_visible_trees(&trees);
+use crate::error::ParseError;
+use crate::grid::Grid;
+use crate::solver::{Answer, Puzzle};
 
-    println!("There are {} visible trees!", ans);
+pub struct Day08;
 
-    let best_score = compute_scenic_score(&trees);
+impl Puzzle for Day08 {
+    /// The height of each tree.
+    type Parsed = Array2<i8>;
 
-    println!("The best possible scenic score is {}", best_score);
-}
+    fn day(&self) -> u32 {
+        8
+    }
 
-fn digit_matrix_to_array(input: &str) -> Array2<i8> {
-    //first read input into vec
-    let in_as_vec: Vec<Vec<i8>> = input
-        .lines()
-        .map(|line| {
-            line.chars()
-                .map(|c| c.to_digit(10).unwrap() as i8)
-                .collect()
-        })
-        .collect();
+    fn title(&self) -> &'static str {
+        "Treetop Tree House"
+    }
+
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Parsed, ParseError<'a>> {
+        digit_matrix_to_array(input)
+    }
 
-    // now get dimensions; skip validation
-    let n_rows = in_as_vec.len();
-    let n_cols = in_as_vec[0].len();
+    fn part1(&self, trees: &Self::Parsed) -> Result<Answer, SimpleError> {
+        let ans = count_visible_trees(trees);
 
-    let mut arr: Array2<i8> = Array2::default((n_rows, n_cols));
-    for (i, mut row) in arr.axis_iter_mut(Axis(0)).enumerate() {
-        for (j, col) in row.iter_mut().enumerate() {
-            *col = in_as_vec[i][j];
-        }
+        Ok(ans.into())
     }
 
-    arr
+    fn part2(&self, trees: &Self::Parsed) -> Result<Answer, SimpleError> {
+        let best_score = compute_scenic_score(trees);
+
+        Ok(best_score.into())
+    }
+}
+
+/// Reads the forest. The visibility scans look at the trees inside the edge, so
+/// there have to be some: the forest must be at least 3 by 3.
+fn digit_matrix_to_array(input: &str) -> Result<Array2<i8>, ParseError<'_>> {
+    let grid = Grid::parse(input, |c| c.to_digit(10).map(|digit| digit as i8))?;
+    if grid.width() < 3 || grid.height() < 3 {
+        return Err(ParseError::new(input, "The forest has to be at least 3 by 3"));
+    }
+    Ok(grid.into())
 }
 
+#[allow(clippy::reversed_empty_ranges)] // ndarray slices, not std ranges
 fn cumulative_max(arr: &Array2<i8>, view: ViewPoint) -> Array2<i8> {
     fn cum_sum_for_array(row: &ArrayView1<i8>) -> Array1<i8> {
         let mut max: i8 = 0;
@@ -69,12 +80,12 @@ fn cumulative_max(arr: &Array2<i8>, view: ViewPoint) -> Array2<i8> {
             cumulative.assign(&cum_sum_for_array(&slice));
         });
 
-    return cum_max;
+    cum_max
 }
 
 fn count_visible_trees(arr: &Array2<i8>) -> usize {
     use ViewPoint::*;
-    let views = vec![Left, Right, Top, Bottom];
+    let views = [Left, Right, Top, Bottom];
 
     fn combine_maps(a: &Array2<bool>, b: &Array2<bool>) -> Array2<bool> {
         Zip::from(a).and(b).map_collect(|a, b| *a || *b)
@@ -90,7 +101,7 @@ fn count_visible_trees(arr: &Array2<i8>) -> usize {
         |acc, el| combine_maps(&acc, el),
     );
 
-    let answer = visible_trees.iter().filter(|&&i| i == true).count();
+    let answer = visible_trees.iter().filter(|&&i| i).count();
 
     let trees_on_perimeter = 2 * (arr.nrows() + arr.ncols()) - 4; //count all edges counts corners twice
     answer + trees_on_perimeter
@@ -104,10 +115,11 @@ enum ViewPoint {
     Bottom,
 }
 
+#[allow(clippy::reversed_empty_ranges)]
 fn compute_visible_trees_from(tree_map: &Array2<i8>, view: ViewPoint) -> Array2<bool> {
     let relevant_trees = tree_map.slice(s![1..-1, 1..-1]); //only inner trees important
 
-    let cum_max = cumulative_max(&tree_map, view);
+    let cum_max = cumulative_max(tree_map, view);
 
     use ViewPoint::*;
     let some_slice = match view {
@@ -132,8 +144,7 @@ fn compute_scenic_score(tree_map: &Array2<i8>) -> usize {
 
     //let final_score = scores[0] * scores[1] * scores[2] * scores[3];
     let final_score = scores.into_iter().reduce(|acc, x| acc * x).unwrap();
-    let best_score = final_score.into_iter().max().unwrap();
-    best_score
+    final_score.into_iter().max().unwrap()
 }
 
 fn compute_num_trees_seen_for_row(line: &ArrayView1<i8>) -> Array1<usize> {
@@ -155,6 +166,7 @@ fn compute_num_trees_seen_for_row(line: &ArrayView1<i8>) -> Array1<usize> {
 
     num_trees_seen
 }
+#[allow(clippy::reversed_empty_ranges)]
 fn compute_num_trees_seen_from_tree(tree_map: &Array2<i8>, view: ViewPoint) -> Array2<usize> {
     use ViewPoint::*;
 
@@ -192,11 +204,16 @@ mod tests {
     fn check_test_building_array() {
         let input = "123\n456\n789";
 
-        let arr: Array2<i8> = digit_matrix_to_array(input);
+        let arr: Array2<i8> = digit_matrix_to_array(input).unwrap();
 
         assert_eq!(1, arr[[0, 0]]);
         assert_eq!(4, arr[[1, 0]]);
         assert_eq!(6, arr[[1, 2]]);
+
+        assert_eq!(digit_matrix_to_array("123\n4x6").unwrap_err().at, "x6");
+        assert_eq!(digit_matrix_to_array("123\n45\n789").unwrap_err().at, "45");
+        assert!(digit_matrix_to_array("0").is_err());
+        assert!(digit_matrix_to_array("123\n456").is_err());
     }
 
     #[test]
@@ -210,7 +227,7 @@ mod tests {
             35390"
         );
 
-        let arr: Array2<i8> = digit_matrix_to_array(input);
+        let arr: Array2<i8> = digit_matrix_to_array(input).unwrap();
 
         // figure out cumulative maximum leftwise
         let cum_max: Array2<i8> = cumulative_max(&arr, ViewPoint::Left);
@@ -227,6 +244,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::reversed_empty_ranges)]
     fn test_compute_visibility() {
         let input = indoc!(
             "
@@ -237,7 +255,7 @@ mod tests {
             35390"
         );
 
-        let arr: Array2<i8> = digit_matrix_to_array(input);
+        let arr: Array2<i8> = digit_matrix_to_array(input).unwrap();
 
         // figure out cumulative maximum leftwise
         let cum_max: Array2<i8> = cumulative_max(&arr, ViewPoint::Left);
@@ -298,7 +316,7 @@ mod tests {
             35390"
         );
 
-        let arr = digit_matrix_to_array(input);
+        let arr = digit_matrix_to_array(input).unwrap();
 
         let result = count_visible_trees(&arr);
         assert_eq!(result, 21);
@@ -323,7 +341,7 @@ mod tests {
             33549
             35390"
         );
-        let arr = digit_matrix_to_array(input);
+        let arr = digit_matrix_to_array(input).unwrap();
         let result = compute_num_trees_seen_from_tree(&arr, ViewPoint::Left);
         let expected = array![[0,1, 2, 3, 1], [0, 1, 1, 1, 2], [0, 1, 1, 1, 1], [0, 1, 2, 1, 4], [0, 1, 1, 3, 1]];
         assert_eq!(result, expected);
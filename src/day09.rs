@@ -1,80 +1,78 @@
-use std::{ops::{Add, Sub}, cmp::max, collections::HashSet};
+use std::collections::HashSet;
 
+use simple_error::SimpleError;
 use strum_macros::EnumString;
 
-pub fn run_day_09(input: String) {
-    
-    let mut game: GameTracker<Situation1> = GameTracker::new();
-    for (m, num_steps) in input.lines().map(read_input_line) {
-        game.apply_move(m, num_steps);
-    }
+use crate::error::ParseError;
+use crate::geometry::{Direction, Vec2};
+use crate::solver::{Answer, Puzzle};
 
-    let ans = game.num_visited_by_tail();
-    println!("Number of unique positions visited by the tail is {}", ans);
+pub struct Day09;
 
-    let mut game: GameTracker<Situation2> = GameTracker::new();
-    for (m, num_steps) in input.lines().map(read_input_line) {
-        game.apply_move(m, num_steps);
-    }
+impl Puzzle for Day09 {
+    /// The moves of the head, with their number of steps.
+    type Parsed = Vec<(Move, usize)>;
 
-    let ans = game.num_visited_by_tail();
-    println!("Number of unique positions visited by the new tail is {}", ans);
-}
+    fn day(&self) -> u32 {
+        9
+    }
 
-fn read_input_line(line: &str) -> (Move, usize) {
-    let split_lines: Vec<&str> = line.split(' ').collect();
-    assert!(split_lines.len() == 2);
-    
-    let m = split_lines[0].parse().unwrap();
-    let num = split_lines[1].parse().unwrap();
+    fn title(&self) -> &'static str {
+        "Rope Bridge"
+    }
 
-    (m, num)
-}
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Parsed, ParseError<'a>> {
+        input.lines().map(read_input_line).collect()
+    }
 
-#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
-struct Vec2D(i32, i32);
+    fn part1(&self, moves: &Self::Parsed) -> Result<Answer, SimpleError> {
+        let mut game: GameTracker<Situation1> = GameTracker::new();
+        for &(m, num_steps) in moves {
+            game.apply_move(m, num_steps);
+        }
 
-impl Vec2D {
-    fn new() -> Self {
-        Self::default()
+        let ans = game.num_visited_by_tail();
+        Ok(ans.into())
     }
 
-    fn inf_norm(&self) -> i32 {
-        max(self.0.abs(), self.1.abs())
+    fn part2(&self, moves: &Self::Parsed) -> Result<Answer, SimpleError> {
+        let mut game: GameTracker<Situation2> = GameTracker::new();
+        for &(m, num_steps) in moves {
+            game.apply_move(m, num_steps);
+        }
+
+        let ans = game.num_visited_by_tail();
+        Ok(ans.into())
     }
 }
 
-impl Add<Vec2D> for Vec2D {
-    type Output = Vec2D;
-
-    fn add(self, rhs: Vec2D) -> Vec2D {
-        Vec2D(self.0 + rhs.0, self.1 + rhs.1)
+fn read_input_line(line: &str) -> Result<(Move, usize), ParseError<'_>> {
+    let split_lines: Vec<&str> = line.split(' ').collect();
+    if split_lines.len() != 2 {
+        return Err(ParseError::new(line, "Expected a direction and a number of steps"));
     }
-}
 
-impl Sub<Vec2D> for Vec2D {
-    type Output = Vec2D;
+    let m = split_lines[0].parse().map_err(|_| ParseError::new(split_lines[0], "Direction has to be one of L, R, U or D"))?;
+    let num = split_lines[1].parse().map_err(|e| ParseError::new(split_lines[1], e))?;
 
-    fn sub(self, rhs: Vec2D) -> Vec2D {
-        Vec2D(self.0 - rhs.0, self.1 - rhs.1)
-    }
+    Ok((m, num))
 }
 
 trait Situation {
     fn new() -> Self;
     fn apply_single_move(&mut self, m: Move);
-    fn get_tail_pos(&self) -> Vec2D;
+    fn get_tail_pos(&self) -> Vec2;
 }
 
 #[derive(Debug, Copy, Clone)]
 struct Situation1 {
-    head: Vec2D,
-    tail: Vec2D
+    head: Vec2,
+    tail: Vec2
 }
 
 impl Situation for Situation1 {
     fn new() -> Self {
-        Situation1{ head: Vec2D::new(), tail: Vec2D::new() }
+        Situation1{ head: Vec2::default(), tail: Vec2::default() }
     }
 
     fn apply_single_move(&mut self, m: Move) {
@@ -84,23 +82,23 @@ impl Situation for Situation1 {
         let mut tail = self.tail;
 
         let dist = head - self.tail;
-        if dist.inf_norm() > 1 {
-            let m = match (dist.0, dist.1) {
-                (d, 2) => Vec2D(d, 1),
-                (d, -2) => Vec2D(d, -1),
-                (2, d) => Vec2D(1, d),
-                (-2, d) => Vec2D(-1, d),
+        if dist.chebyshev_norm() > 1 {
+            let m = match (dist.x, dist.y) {
+                (d, 2) => Vec2::new(d, 1),
+                (d, -2) => Vec2::new(d, -1),
+                (2, d) => Vec2::new(1, d),
+                (-2, d) => Vec2::new(-1, d),
                 (x, y) => panic!("Should never see something like {:?}", (x, y))
             };
 
-            tail = tail + m;    
+            tail += m;
         }
 
         self.head = head;
         self.tail = tail;
     }
 
-    fn get_tail_pos(&self) -> Vec2D {
+    fn get_tail_pos(&self) -> Vec2 {
         self.tail
     }
 
@@ -108,7 +106,7 @@ impl Situation for Situation1 {
 
 struct GameTracker<S: Situation> {
     situation: S,
-    visited_by_tail: HashSet<Vec2D>
+    visited_by_tail: HashSet<Vec2>
 }
 
 impl<S: Situation> GameTracker<S> {
@@ -133,7 +131,7 @@ impl<S: Situation> GameTracker<S> {
 }
 
 #[derive(Debug, Clone, Copy, EnumString, PartialEq)]
-enum Move {
+pub enum Move {
     L,
     R,
     U,
@@ -141,43 +139,40 @@ enum Move {
 }
 
 impl Move {
-    fn to_vec(&self) -> Vec2D {
-        use Move::*;
-        match self {
-            L => Vec2D(-1, 0),
-            R => Vec2D(1, 0),
-            U => Vec2D(0, 1),
-            D => Vec2D(0, -1)
-        }
+    fn to_vec(self) -> Vec2 {
+        let direction = match self {
+            Move::L => Direction::Left,
+            Move::R => Direction::Right,
+            Move::U => Direction::Up,
+            Move::D => Direction::Down,
+        };
+        direction.offset()
     }
 }
 
 #[derive(Debug)]
 struct Situation2 {
-    knots: [Vec2D; 10]
+    knots: [Vec2; 10]
 }
 
 impl Situation for Situation2 {
     fn new() -> Self {
-        Situation2{knots: [Vec2D::new(); 10]}
+        Situation2{knots: [Vec2::default(); 10]}
     }
 
     fn apply_single_move(&mut self, m: Move) {
-        self.knots[0] = self.knots[0] + m.to_vec();
+        self.knots[0] += m.to_vec();
 
         for i in 1..10 {
             let distance_to_target = self.knots[i-1] - self.knots[i];
 
-            if distance_to_target.inf_norm() > 1 {
-                let dx = distance_to_target.0.signum();
-                let dy = distance_to_target.1.signum();
-                let delta = Vec2D(dx, dy);
-                self.knots[i] = self.knots[i] + delta;
+            if distance_to_target.chebyshev_norm() > 1 {
+                self.knots[i] += distance_to_target.signum();
             }
         }
     }
 
-    fn get_tail_pos(&self) -> Vec2D {
+    fn get_tail_pos(&self) -> Vec2 {
         self.knots[9]
     }
 }
@@ -186,28 +181,22 @@ impl Situation for Situation2 {
 mod tests {
     use super::*;
     
-    #[test]
-    fn some_vec_tests() {
-        let v = Vec2D::default();
-        assert_eq!((0, 0), (v.0, v.1));
-    }
-
     #[test]
     fn test_simple_move_stuff() {
         let mut situation = Situation1::new();
         situation.apply_single_move(Move::R);
-        let expected_tail = Vec2D(0, 0);
+        let expected_tail = Vec2::new(0, 0);
         assert_eq!(expected_tail, situation.tail);
 
         situation.apply_single_move(Move::R);
-        let expected_tail = Vec2D(1, 0);
+        let expected_tail = Vec2::new(1, 0);
         assert_eq!(expected_tail, situation.tail);
 
         situation.apply_single_move(Move::U);
         assert_eq!(expected_tail, situation.tail);
 
         situation.apply_single_move(Move::U);
-        let expected_tail = Vec2D(2, 1);
+        let expected_tail = Vec2::new(2, -1);
         assert_eq!(expected_tail, situation.tail);
     }
 
@@ -222,8 +211,12 @@ mod tests {
 
     #[test]
     fn test_read_input_line() {
-        let (m, num) = read_input_line("R 4");
+        let (m, num) = read_input_line("R 4").unwrap();
         assert_eq!(m, Move::R);
         assert_eq!(num, 4);
+
+        assert_eq!(read_input_line("X 4").unwrap_err().at, "X");
+        assert_eq!(read_input_line("R -4").unwrap_err().at, "-4");
+        assert_eq!(read_input_line("R4").unwrap_err().at, "R4");
     }
 }
\ No newline at end of file
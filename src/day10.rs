@@ -1,20 +1,47 @@
 use crate::device::{CPUtracker, render};
+use simple_error::SimpleError;
 
-pub fn run_day_10(input: String) {
+use crate::error::{parse_lines, ParseError};
+use crate::solver::{Answer, Puzzle};
+
+pub struct Day10;
+
+impl Puzzle for Day10 {
+    /// The value of the X register during every cycle of the program.
+    type Parsed = CPUtracker;
+
+    fn day(&self) -> u32 {
+        10
+    }
+
+    fn title(&self) -> &'static str {
+        "Cathode-Ray Tube"
+    }
+
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Parsed, ParseError<'a>> {
+        run_program(input)
+    }
+
+    fn part1(&self, tracker: &Self::Parsed) -> Result<Answer, SimpleError> {
+        // This is simplest way
+        let ans: i32 = (1..).zip(tracker.all_xs())
+            .skip(19)
+            .step_by(40)
+            .take(6).map(|(i, signal)| i * signal).sum();
+
+        Ok(ans.into())
+    }
+
+    fn part2(&self, tracker: &Self::Parsed) -> Result<Answer, SimpleError> {
+        Ok(Answer::Crt(render(tracker.all_xs().cloned())))
+    }
+}
+
+fn run_program(input: &str) -> Result<CPUtracker, ParseError<'_>> {
     let mut tracker = CPUtracker::new();
 
-    for instruction in input.lines().map(|line| line.parse().unwrap()) {
+    for instruction in parse_lines(input, str::parse)? {
         tracker.execute(instruction);
     }
-
-    // This is simplest way
-    let ans: i32 = (1..).zip(tracker.all_xs())
-        .skip(19)
-        .step_by(40)
-        .take(6).map(|(i, signal)| i * signal).sum();
-    
-    println!("Multiplying stuff like in part 1 gives answer {}", ans);
-
-    let crt_output = render(tracker.all_xs().cloned());
-    println!("{}", crt_output);
-}
\ No newline at end of file
+    Ok(tracker)
+}
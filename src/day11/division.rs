@@ -1,5 +1,7 @@
 use lazy_regex::regex_captures;
 
+use crate::error::ParseError;
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct DivisibleTest {
     divisor: u64,
@@ -13,7 +15,7 @@ impl DivisibleTest {
     }
 
     pub fn get_target_monkey_for(&self, x: u64) -> usize {
-        if x % self.divisor == 0 {
+        if x.is_multiple_of(self.divisor) {
             self.true_monkey
         } else {
             self.false_monkey
@@ -24,14 +26,30 @@ impl DivisibleTest {
         self.divisor
     }
 
-    pub fn from_lines(lines: &[&str; 3]) -> Self {
+    pub fn get_target_monkeys(&self) -> [usize; 2] {
+        [self.true_monkey, self.false_monkey]
+    }
+
+    pub fn from_lines<'a>(lines: &[&'a str; 3]) -> Result<Self, ParseError<'a>> {
         let (test_line, true_line, false_line) = (lines[0], lines[1], lines[2]);
 
-        let divisor: u64 = regex_captures!(r"Test: divisible by (\d+)", test_line).unwrap().1.parse().unwrap();
-        let true_monkey: usize = regex_captures!(r"If true: throw to monkey (\d+)", true_line).unwrap().1.parse().unwrap();
-        let false_monkey: usize = regex_captures!(r"If false: throw to monkey (\d+)", false_line).unwrap().1.parse().unwrap();
+        let (_, divisor) = regex_captures!(r"Test: divisible by (\d+)", test_line)
+            .ok_or_else(|| ParseError::new(test_line, "Expected 'Test: divisible by <number>'"))?;
+        let (_, true_monkey) = regex_captures!(r"If true: throw to monkey (\d+)", true_line)
+            .ok_or_else(|| ParseError::new(true_line, "Expected 'If true: throw to monkey <number>'"))?;
+        let (_, false_monkey) = regex_captures!(r"If false: throw to monkey (\d+)", false_line)
+            .ok_or_else(|| ParseError::new(false_line, "Expected 'If false: throw to monkey <number>'"))?;
 
-        DivisibleTest { divisor, true_monkey, false_monkey }
+        let divisor: u64 = divisor.parse().map_err(|e| ParseError::new(test_line, e))?;
+        if divisor == 0 {
+            return Err(ParseError::new(test_line, "Nothing is divisible by 0"));
+        }
+
+        Ok(DivisibleTest {
+            divisor,
+            true_monkey: true_monkey.parse().map_err(|e| ParseError::new(true_line, e))?,
+            false_monkey: false_monkey.parse().map_err(|e| ParseError::new(false_line, e))?,
+        })
     }
 }
 
@@ -52,9 +70,14 @@ mod tests {
     #[test]
     fn test_from_lines() {
         let lines = ["  Test: divisible by 23", "    If true: throw to monkey 2", "     If false: throw to monkey 3"];
-        let div_test = DivisibleTest::from_lines(&lines);
+        let div_test = DivisibleTest::from_lines(&lines).unwrap();
 
         assert_eq!(2, div_test.get_target_monkey_for(46));
         assert_eq!(3, div_test.get_target_monkey_for(47));
+
+        let lines = ["  Test: divisible by 0", lines[1], lines[2]];
+        assert_eq!(DivisibleTest::from_lines(&lines).unwrap_err().at, lines[0]);
+        let lines = [lines[1], lines[1], lines[2]];
+        assert_eq!(DivisibleTest::from_lines(&lines).unwrap_err().at, lines[0]);
     }
 }
\ No newline at end of file
@@ -1,40 +1,63 @@
-use std::{str::FromStr, error::Error};
+use simple_error::SimpleError;
 
 pub use self::monkey::{Monkey, MonkeyMove};
+use crate::error::ParseError;
+use crate::solver::{Answer, Puzzle};
 
 mod operation;
 mod division;
 mod monkey;
 
-pub fn run_day_11(input: String) {
-    let mut game: MonkeyGame = input.clone().parse().unwrap();
+pub struct Day11;
 
-    for _ in 0..20 {
-        game.play_round();
+impl Puzzle for Day11 {
+    /// The monkeys with their starting items.
+    type Parsed = MonkeyGame;
+
+    fn day(&self) -> u32 {
+        11
     }
 
-    // for the small num of monkeys, sorting isn't the worst
-    let mut monkey_business = game.get_monkey_business();
-    monkey_business.sort();
+    fn title(&self) -> &'static str {
+        "Monkey in the Middle"
+    }
 
-    let ans = monkey_business[monkey_business.len() - 1] * monkey_business[monkey_business.len() - 2];
-    println!("The current monkey business score is {}", ans);
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Parsed, ParseError<'a>> {
+        MonkeyGame::parse(input)
+    }
 
-    // new game
-    let mut game: MonkeyGame = input.clone().parse().unwrap();
-    game.set_divisor(1);
+    fn part1(&self, game: &Self::Parsed) -> Result<Answer, SimpleError> {
+        let mut game = game.clone();
+
+        for _ in 0..20 {
+            game.play_round();
+        }
 
-    for _ in 0..10000 {
-        game.play_round();
+        // for the small num of monkeys, sorting isn't the worst
+        let mut monkey_business = game.get_monkey_business();
+        monkey_business.sort();
+
+        let ans = monkey_business[monkey_business.len() - 1] * monkey_business[monkey_business.len() - 2];
+        Ok(ans.into())
     }
 
-    let mut monkey_business = game.get_monkey_business();
-    monkey_business.sort();
+    fn part2(&self, game: &Self::Parsed) -> Result<Answer, SimpleError> {
+        let mut game = game.clone();
+        game.set_divisor(1);
+
+        for _ in 0..10000 {
+            game.play_round();
+        }
+
+        let mut monkey_business = game.get_monkey_business();
+        monkey_business.sort();
 
-    let ans = monkey_business[monkey_business.len() - 1] * monkey_business[monkey_business.len() - 2];
-    println!("The current monkey business score is {}", ans);
+        let ans = monkey_business[monkey_business.len() - 1] * monkey_business[monkey_business.len() - 2];
+        Ok(ans.into())
+    }
 }
 
+#[derive(Clone)]
 pub struct MonkeyGame {
     monkeys: Vec<Monkey>,
     monkey_item_counter: Vec<usize>,
@@ -46,7 +69,26 @@ impl MonkeyGame {
     pub fn new(monkeys: Vec<Monkey>, lcm: u64) -> Self {
         let counter = vec![0usize; monkeys.len()];
 
-        MonkeyGame{ monkeys: monkeys, monkey_item_counter: counter, lcm: lcm }
+        MonkeyGame{ monkeys, monkey_item_counter: counter, lcm }
+    }
+
+    pub fn parse(s: &str) -> Result<Self, ParseError<'_>> {
+        let blocks: Vec<&str> = s.trim_end().split("\n\n").collect();
+
+        let monkeys: Vec<Monkey> = blocks.iter().map(|block| Monkey::parse(block)).collect::<Result<_, _>>()?;
+        // the monkey business is about the two most active monkeys
+        if monkeys.len() < 2 {
+            return Err(ParseError::end_of_input("There have to be at least two monkeys"));
+        }
+
+        for (monkey, block) in monkeys.iter().zip(&blocks) {
+            if let Some(target) = monkey.target_monkeys().into_iter().find(|target| *target >= monkeys.len()) {
+                return Err(ParseError::new(block, format!("There's no monkey {} to throw to", target)));
+            }
+        }
+
+        let lcm = monkeys.iter().map(|monkey| monkey.get_prime_test()).product();
+        Ok(MonkeyGame::new(monkeys, lcm))
     }
 
     pub fn play_round(&mut self) {
@@ -70,23 +112,6 @@ impl MonkeyGame {
     }
 }
 
-impl FromStr for MonkeyGame {
-    type Err = Box<dyn Error>;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let blocks = s.split("\n\n");
-
-        // let monkeys= blocks.map(|block| block.parse()).collect::<Result<_,_>>()?;
-        let mut monkeys: Vec<Monkey> = Vec::new();
-
-        for block in blocks {
-            monkeys.push(block.parse()?);
-        }
-
-        let lcm = monkeys.iter().map(|monkey| monkey.get_prime_test()).product();
-        Ok(MonkeyGame::new(monkeys, lcm))
-    }
-}
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -124,8 +149,22 @@ Monkey 3:
     If false: throw to monkey 1
 ");
 
-    let monkeygame: MonkeyGame = input.parse().unwrap();
+    let monkeygame = MonkeyGame::parse(input).unwrap();
 
     assert_eq!(monkeygame.monkeys.len(), 4);
     }
+
+    #[test]
+    fn one_monkey_is_not_enough() {
+        let input = indoc!("
+            Monkey 0:
+              Starting items: 79, 98
+              Operation: new = old * 19
+              Test: divisible by 23
+                If true: throw to monkey 0
+                If false: throw to monkey 0");
+
+        let error = MonkeyGame::parse(input).err().unwrap();
+        assert_eq!(error.reason, "There have to be at least two monkeys");
+    }
 }
\ No newline at end of file
@@ -1,14 +1,14 @@
-use std::error::Error;
 use std::num::ParseIntError;
-use std::str::FromStr;
 
 use itertools::Itertools;
 
 use crate::day11::division::DivisibleTest;
 use crate::day11::operation::Operation;
+use crate::error::ParseError;
 use lazy_regex::regex;
 
 
+#[derive(Clone)]
 pub struct Monkey {
     test: DivisibleTest,
     op: Operation,
@@ -49,19 +49,24 @@ pub struct MonkeyMove {
     pub target_monkey: usize
 }
 
-impl FromStr for Monkey {
-    type Err = Box<dyn Error>;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
+impl Monkey {
+    pub fn parse(s: &str) -> Result<Self, ParseError<'_>> {
         let lines = s.lines().skip(1).collect_vec();
-        
-        let start_items = parse_start_items(lines[0])?;
-        let operation: Operation = lines[1].parse()?;
-        
-        let test: DivisibleTest = DivisibleTest::from_lines(lines[2..5].try_into()?);
+        if lines.len() != 5 {
+            return Err(ParseError::new(s, "Expected a monkey header and five lines about the monkey"));
+        }
+
+        let start_items = parse_start_items(lines[0]).map_err(|e| ParseError::new(lines[0], e))?;
+        let operation: Operation = lines[1].parse().map_err(|e| ParseError::new(lines[1], e))?;
+
+        let test: DivisibleTest = DivisibleTest::from_lines(&[lines[2], lines[3], lines[4]])?;
 
         Ok(Self::new(start_items, operation, test))
     }
+
+    pub fn target_monkeys(&self) -> [usize; 2] {
+        self.test.get_target_monkeys()
+    }
 }
 
 fn parse_start_items(line: &str) -> Result<Vec<u64>, ParseIntError> {
@@ -103,10 +108,15 @@ mod tests {
         "
         );
 
-        let monkey: Monkey = input.parse().unwrap();
+        let monkey = Monkey::parse(input).unwrap();
 
         assert_eq!(monkey.items, vec![79, 98]);
         assert_eq!(monkey.op, Operation::Mul(19));
         assert_eq!(monkey.test, DivisibleTest::new(23, 2, 3));
+
+        let broken = input.replace("old * 19", "old ^ 19");
+        let error = Monkey::parse(&broken).err().unwrap();
+        assert_eq!(error.at.trim(), "Operation: new = old ^ 19");
+        assert!(Monkey::parse("Monkey 0:\n  Starting items: 79, 98").is_err());
     }
 }
\ No newline at end of file
@@ -26,12 +26,10 @@ impl FromStr for Operation {
         let s_trimmed = s.trim();
         if s_trimmed == "Operation: new = old * old" {
             Ok(Operation::Square)
-        } else if s_trimmed.starts_with("Operation: new = old + ") {
-            let num: u64 = s_trimmed[23..].parse()?;
-            Ok(Operation::Add(num))
-        } else if s_trimmed.starts_with("Operation: new = old * ") {
-            let num: u64 = s_trimmed[23..].parse()?;
-            Ok(Operation::Mul(num))
+        } else if let Some(num) = s_trimmed.strip_prefix("Operation: new = old + ") {
+            Ok(Operation::Add(num.parse()?))
+        } else if let Some(num) = s_trimmed.strip_prefix("Operation: new = old * ") {
+            Ok(Operation::Mul(num.parse()?))
         } else {
             Err(Box::new(SimpleError::new("Not a valid operation")))
         }
@@ -1,185 +1,349 @@
-use std::{cmp::Reverse, collections::{HashSet, HashMap}};
+use simple_error::SimpleError;
+use toml::Table;
 
-use itertools::Itertools;
-use priority_queue::PriorityQueue;
+use crate::error::ParseError;
+use crate::geometry::{Direction8, Vec2};
+use crate::grid::{Grid, GridView};
+use crate::picture::{gradient, Cell, Picture, Rgb};
+use crate::search::dijkstra;
+use crate::solver::{Answer, DrawOptions, Puzzle};
 
-pub fn run_day_12(input: String) {
-    let (terrain, start, stop) = read_terrain(&input);
+/// The hill climbing puzzle, searched with a movement `policy`.
+#[derive(Debug, Clone, Copy)]
+pub struct Day12 {
+    pub policy: MovementPolicy,
+}
 
-    let (shortest_path, ans) = find_path_length(&terrain, start, stop).unwrap();
+impl Day12 {
+    pub const PUZZLE: Day12 = Day12 { policy: MovementPolicy::PUZZLE };
+}
 
-    println!("It takes {} steps to reach the end", ans);
-    assert_eq!(ans, shortest_path.len() as i32 - 1);
-    let ans = find_best_start(&terrain, stop);
+impl Puzzle for Day12 {
+    /// The height map, the start and the location with the best signal.
+    type Parsed = (Grid<i8>, Vec2, Vec2);
 
-    println!("But from the best start it only takes {} steps to reach the end", ans);
-}
+    fn day(&self) -> u32 {
+        12
+    }
+
+    fn title(&self) -> &'static str {
+        "Hill Climbing Algorithm"
+    }
 
-fn find_best_start(terrain: &Vec<Vec<i8>>, stop: Vec2D) -> i32 {
-    let mut starts_set: HashSet<Vec2D> = get_all_possible_starts(terrain).into_iter().collect();
-    let mut best_length: Option<i32> = None;
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Parsed, ParseError<'a>> {
+        read_terrain(input)
+    }
 
-    while let Some(&start) = starts_set.iter().next() {
-        starts_set.remove(&start);
-        if let Some((path, ans)) = find_path_length(terrain, start, stop) {
-            
-            for node in path.iter() {
-                starts_set.remove(node);
-            }
+    fn part1(&self, (terrain, start, stop): &Self::Parsed) -> Result<Answer, SimpleError> {
+        let (_, ans) = find_path_length(terrain, *start, *stop, &self.policy)
+            .ok_or_else(|| SimpleError::new("There's no way up from S to E"))?;
 
-            let (steps, _) = path.into_iter().enumerate()
-            .filter(|(_, node)| get_height(terrain, node) == 1).last().unwrap();
+        Ok(ans.into())
+    }
 
-            let length = ans - steps as i32;
-            best_length = Some(best_length.map_or_else(|| length, |x| std::cmp::min(x, length)));
-            
-        }
+    fn part2(&self, (terrain, _, stop): &Self::Parsed) -> Result<Answer, SimpleError> {
+        let (_, ans) = find_best_start(terrain, *stop, &self.policy)
+            .ok_or_else(|| SimpleError::new("There's no way up to E from any square of elevation a"))?;
 
+        Ok(ans.into())
     }
-    best_length.unwrap()
-}
 
-fn find_path_length(terrain: &Vec<Vec<i8>>, start: Vec2D, stop: Vec2D) -> Option<(Vec<Vec2D>, i32)> {
-    let mut steps_from_start: PriorityQueue<Vec2D, Reverse<i32>> = PriorityQueue::new();
-    let mut visited: HashSet<Vec2D> = HashSet::new();
-    let mut predecessors: HashMap<Vec2D, Vec2D> = HashMap::new();
-
-    steps_from_start.push(start, Reverse(0));
-    visited.insert(start);
-
-    let rows = terrain.len();
-    let cols = terrain[0].len();
-
-    while !steps_from_start.is_empty() {
-        let (current_node, Reverse(current_dist)) = steps_from_start.pop().unwrap(); // node with currently shortest paths
-        
-        if current_node == stop {
-            // build the path
-            let mut shortest_path = vec![];
-            shortest_path.push(current_node);
-            let mut active_node = current_node;
-            while let Some(node) = predecessors.get(&active_node) {
-                active_node = *node;
-                shortest_path.push(active_node);
-            }
-            // just for fun put it in the right order
-            let shortest_path = shortest_path.into_iter().rev().collect();
-            return Some((shortest_path, current_dist));
+    /// The shortest path from S to E as arrows, like in the puzzle, over the
+    /// hills coloured by height.
+    fn draw(&self, (terrain, start, stop): &Self::Parsed, _options: &DrawOptions) -> Option<Picture> {
+        let (path, _) = find_path_length(terrain, *start, *stop, &self.policy)?;
+        let mut cells = terrain.map(|&height| Cell { symbol: '.', colour: height_colour(height) });
+
+        for step in path.windows(2) {
+            cells[step[0]] = Cell { symbol: arrow(step[1] - step[0])?, colour: PATH_COLOUR };
         }
+        cells[*stop] = Cell { symbol: 'E', colour: PATH_COLOUR };
+        Some(Picture { cells })
+    }
 
-        let current_height = get_height(terrain, &current_node);
-    
-        for neighbor in get_adjacent_nodes(&current_node, rows, cols) {
-            if get_height(terrain, &neighbor) <= current_height + 1 {
-                if visited.insert(neighbor) { // returns true if new in set
-                    steps_from_start.push(neighbor, Reverse(current_dist + 1));
-                    predecessors.insert(neighbor, current_node);
+    /// `max_ascent`, `max_descent` and `diagonals` of the movement policy.
+    /// The `step_cost` is a function, so it can only be changed in code.
+    fn with_settings(&self, settings: &Table) -> Result<Self, String> {
+        let mut day = *self;
+        for (name, value) in settings {
+            let number = || {
+                let number = value.as_integer().ok_or_else(|| format!("Setting '{}' isn't a number", name))?;
+                number.try_into().map_err(|_| format!("Setting '{}' is out of range", name))
+            };
+            match name.as_str() {
+                "max_ascent" => day.policy.max_ascent = number()?,
+                "max_descent" => day.policy.max_descent = Some(number()?),
+                "diagonals" => {
+                    day.policy.diagonals = value.as_bool().ok_or_else(|| format!("Setting '{}' isn't true or false", name))?
                 }
+                _ => return Err(format!("There's no setting '{}'", name)),
             }
         }
+        Ok(day)
     }
-    None
 }
 
-fn get_height(terrain: &Vec<Vec<i8>>, pos: &Vec2D) -> i8 {
-    terrain[pos.0 as usize][pos.1 as usize]
+/// Valleys are green, slopes brown and peaks covered in snow.
+const HEIGHT_COLOURS: [Rgb; 4] = [[20, 90, 30], [110, 160, 60], [140, 100, 60], [240, 240, 250]];
+const PATH_COLOUR: Rgb = [230, 30, 30];
+
+fn height_colour(height: i8) -> Rgb {
+    gradient(&HEIGHT_COLOURS, (height - 1) as f64 / 25.0)
 }
 
-fn get_adjacent_nodes(node: &Vec2D, rows: usize, cols: usize) -> Vec<Vec2D> {
-    let check = |new_node: &Vec2D| {
-        !(new_node.0 < 0
-            || new_node.1 < 0
-            || new_node.0 >= rows as i32
-            || new_node.1 >= cols as i32)
+/// The arrow for a step to a neighbour, or `None` if `step` goes further.
+fn arrow(step: Vec2) -> Option<char> {
+    let arrow = match (step.x, step.y) {
+        (1, 0) => '>',
+        (-1, 0) => '<',
+        (0, -1) => '^',
+        (0, 1) => 'v',
+        (1, -1) => '↗',
+        (1, 1) => '↘',
+        (-1, 1) => '↙',
+        (-1, -1) => '↖',
+        _ => return None,
     };
+    Some(arrow)
+}
 
-    [
-        (node.0 + 1, node.1),
-        (node.0 - 1, node.1),
-        (node.0, node.1 + 1),
-        (node.0, node.1 - 1),
-    ]
-    .into_iter()
-    .map(|(x, y)| Vec2D(x, y))
-    .filter(check)
-    .collect_vec()
+/// How we may move over the terrain, and what that costs.
+#[derive(Debug, Clone, Copy)]
+pub struct MovementPolicy {
+    /// How much higher the next square can be.
+    pub max_ascent: i8,
+    /// How much lower the next square can be, or `None` for as far down as we like.
+    pub max_descent: Option<i8>,
+    pub diagonals: bool,
+    /// The cost of a step, given how much higher the next square is. Going
+    /// down is a negative climb.
+    pub step_cost: fn(i8) -> u32,
 }
 
-fn read_terrain(input: &str) -> (Vec<Vec<i8>>, Vec2D, Vec2D) {
-    let mut terrain = Vec::new();
+impl Default for MovementPolicy {
+    fn default() -> Self {
+        MovementPolicy::PUZZLE
+    }
+}
 
-    let mut start_pos = Vec2D(0, 0);
-    let mut end_pos = Vec2D(0, 0);
+impl MovementPolicy {
+    /// The puzzle's rules: at most one higher, any way down, no diagonals and
+    /// every step counts the same.
+    pub const PUZZLE: MovementPolicy =
+        MovementPolicy { max_ascent: 1, max_descent: None, diagonals: false, step_cost: |_| 1 };
 
-    for (row, line) in input.lines().enumerate() {
-        terrain.push(vec![]);
-        for (col, letter) in line.chars().enumerate() {
-            terrain[row].push(letter_to_height(letter));
-            if letter == 'S' {
-                start_pos = Vec2D(row as i32, col as i32);
-            } else if letter == 'E' {
-                end_pos = Vec2D(row as i32, col as i32);
-            }
-        }
+    fn allows(&self, climb: i8) -> bool {
+        climb <= self.max_ascent && self.max_descent.is_none_or(|max| -climb <= max)
     }
 
-    (terrain, start_pos, end_pos)
-}
+    /// The squares around `pos` we may step to if the climb is fine.
+    fn neighbours<'a>(&self, terrain: &'a Grid<i8>, pos: Vec2) -> impl Iterator<Item = Vec2> + 'a {
+        let diagonals = self.diagonals;
+        Direction8::ALL
+            .into_iter()
+            .map(|dir| dir.offset())
+            .filter(move |offset| diagonals || offset.manhattan_norm() == 1)
+            .map(move |offset| pos + offset)
+            .filter(|next| terrain.contains(*next))
+    }
 
-fn letter_to_height(letter: char) -> i8 {
-    match letter {
-        'S' => 1,
-        'E' => 26,
-        _ => {
-            let code = letter as i8;
-            if code >= ('a' as i8) && code <= ('z' as i8) {
-                code - ('a' as i8) + 1
-            } else {
-                panic!("Invalid input.")
-            }
-        }
+    /// The squares we can step to from `pos`, with the cost of the step.
+    fn steps_from<'a>(&self, terrain: &'a Grid<i8>, pos: Vec2) -> impl Iterator<Item = (Vec2, u32)> + 'a {
+        let policy = *self;
+        self.neighbours(terrain, pos)
+            .map(move |next| (next, terrain[next] - terrain[pos]))
+            .filter(move |&(_, climb)| policy.allows(climb))
+            .map(move |(next, climb)| (next, (policy.step_cost)(climb)))
     }
-}
 
-fn get_all_possible_starts(terrain: &Vec<Vec<i8>>) -> Vec<Vec2D> {
-    let mut starts = vec![];
-    for (i, row) in terrain.iter().enumerate() {
-        for (j, height) in row.iter().enumerate() {
-            if *height == 1 {
-                starts.push(Vec2D(i as i32, j as i32));
-            }
-        }
+    /// The squares we could have come from to get to `pos`, with the cost of
+    /// that step. For searching backwards.
+    fn steps_to<'a>(&self, terrain: &'a Grid<i8>, pos: Vec2) -> impl Iterator<Item = (Vec2, u32)> + 'a {
+        let policy = *self;
+        self.neighbours(terrain, pos)
+            .map(move |previous| (previous, terrain[pos] - terrain[previous]))
+            .filter(move |&(_, climb)| policy.allows(climb))
+            .map(move |(previous, climb)| (previous, (policy.step_cost)(climb)))
     }
-    starts
 }
 
-#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
-struct Vec2D(i32, i32);
+/// Walks down from `stop` until the first square of elevation a. That's the
+/// start with the cheapest way up, because going down is climbing backwards.
+/// Returns the start and the cost of the way up from there.
+fn find_best_start(terrain: &Grid<i8>, stop: Vec2, policy: &MovementPolicy) -> Option<(Vec2, u32)> {
+    let is_lowest = |pos: &Vec2| get_height(terrain, pos) == 1;
+    let result = dijkstra([stop], |&pos| policy.steps_to(terrain, pos), is_lowest);
+
+    let start = *result.goal()?;
+    Some((start, result.distance(&start)?))
+}
+
+/// The cheapest path from `start` to `stop`, both included, and its cost.
+fn find_path_length(
+    terrain: &Grid<i8>,
+    start: Vec2,
+    stop: Vec2,
+    policy: &MovementPolicy,
+) -> Option<(Vec<Vec2>, u32)> {
+    let result = dijkstra([start], |&pos| policy.steps_from(terrain, pos), |&pos| pos == stop);
+
+    let shortest_path = result.path_to(&stop)?;
+    let cost = result.distance(&stop)?;
+    Some((shortest_path, cost))
+}
+
+fn get_height(terrain: &Grid<i8>, pos: &Vec2) -> i8 {
+    terrain[*pos]
+}
+
+fn read_terrain(input: &str) -> Result<(Grid<i8>, Vec2, Vec2), ParseError<'_>> {
+    let letters = Grid::parse(input, |letter| letter_to_height(letter).map(|_| letter))?;
+
+    let start_pos = letters.find(|&letter| letter == 'S')
+        .ok_or_else(|| ParseError::new(input, "The map has no start S"))?;
+    let end_pos = letters.find(|&letter| letter == 'E')
+        .ok_or_else(|| ParseError::new(input, "The map has no location E with the best signal"))?;
+
+    let terrain = letters.map(|&letter| letter_to_height(letter).unwrap());
+    Ok((terrain, start_pos, end_pos))
+}
+
+fn letter_to_height(letter: char) -> Option<i8> {
+    match letter {
+        'S' => Some(1),
+        'E' => Some(26),
+        'a'..='z' => Some((letter as u8 - b'a' + 1) as i8),
+        _ => None,
+    }
+}
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::solver::Solver;
     use indoc::indoc;
 
-    #[test]
-    fn test_read_map() {
-        let map = indoc!(
-            "
+    const EXAMPLE: &str = indoc! {"
         Sabqponm
         abcryxxl
         accszExk
         acctuvwj
-        abdefghi"
+        abdefghi"};
+
+    #[test]
+    fn test_read_map() {
+
+        let (terrain, start, stop) = read_terrain(EXAMPLE).unwrap();
+        assert_eq!(terrain.height(), 5);
+        assert_eq!(terrain.width(), 8);
+
+        assert_eq!(start, Vec2::new(0, 0));
+        assert_eq!(stop, Vec2::new(5, 2));
+
+        assert_eq!(get_height(&terrain, &start), 1);
+        assert_eq!(get_height(&terrain, &stop), 26);
+
+        assert_eq!(read_terrain("Sab\nc1E").unwrap_err().at, "1E");
+        assert_eq!(read_terrain("Sab\ncE").unwrap_err().at, "cE");
+        assert!(read_terrain("Sab\ncde").is_err());
+    }
+
+    #[test]
+    fn best_start_is_found_walking_down() {
+        let (terrain, start, stop) = read_terrain(EXAMPLE).unwrap();
+
+        let policy = MovementPolicy::default();
+        assert_eq!(find_best_start(&terrain, stop, &policy), Some((Vec2::new(0, 4), 29)));
+        assert_eq!(find_path_length(&terrain, Vec2::new(0, 4), stop, &policy).unwrap().1, 29);
+        assert_eq!(find_path_length(&terrain, start, stop, &policy).unwrap().1, 31);
+
+        let (terrain, _, stop) = read_terrain("SacE").unwrap();
+        assert_eq!(find_best_start(&terrain, stop, &policy), None);
+    }
+
+    #[test]
+    fn other_movement_policies() {
+        let (terrain, start, stop) = read_terrain(EXAMPLE).unwrap();
+        let path_cost = |policy| find_path_length(&terrain, start, stop, &policy).map(|(_, cost)| cost);
+
+        let diagonals = MovementPolicy { diagonals: true, ..Default::default() };
+        assert_eq!(path_cost(diagonals), Some(27));
+        assert_eq!(find_best_start(&terrain, stop, &diagonals).unwrap().1, 26);
+
+        // climbing two at a time lets us cut across the ridge
+        let steep = MovementPolicy { max_ascent: 2, ..Default::default() };
+        assert!(path_cost(steep).unwrap() < 31);
+
+        // the way up is the same, but we can't get down once we're up
+        let careful = MovementPolicy { max_descent: Some(0), ..Default::default() };
+        assert_eq!(path_cost(careful), Some(31));
+        let careful = MovementPolicy { max_ascent: 0, max_descent: Some(0), ..Default::default() };
+        assert_eq!(path_cost(careful), None);
+
+        // climbing costs twice as much as walking on the level, going down is free
+        let step_cost = |climb: i8| match climb {
+            1.. => 2,
+            0 => 1,
+            _ => 0,
+        };
+        let weighted = MovementPolicy { step_cost, ..Default::default() };
+        let (path, cost) = find_path_length(&terrain, start, stop, &weighted).unwrap();
+        let climbs = path.windows(2).filter(|step| terrain[step[1]] > terrain[step[0]]).count() as u32;
+        let levels = path.windows(2).filter(|step| terrain[step[1]] == terrain[step[0]]).count() as u32;
+        assert_eq!(cost, 2 * climbs + levels);
+    }
+
+    #[test]
+    fn draws_the_path_with_arrows() {
+        let picture = Puzzle::draw(&Day12::PUZZLE, &read_terrain(EXAMPLE).unwrap(), &DrawOptions::default()).unwrap();
+
+        // there are several shortest paths, which differ near the start, but
+        // they all end the way the puzzle draws it
+        let text = picture.to_text();
+        let ending = indoc!(
+            "
+        ..vv>E^^
+        ..v>>>^^
+        ..>>>>>^"
         );
+        assert!(text.ends_with(&ending[2..]), "{}", text);
+        assert_eq!(arrow(Vec2::new(2, 0)), None);
 
-        let (terrain, start, stop) = read_terrain(map);
-        assert_eq!(terrain.len(), 5);
-        assert_eq!(terrain[0].len(), 8);
+        // following the arrows from S gets us to E in the fewest steps
+        let (mut pos, mut steps) = (Vec2::new(0, 0), 0);
+        while picture.cells[pos].symbol != 'E' {
+            let dir = match picture.cells[pos].symbol {
+                '>' => Direction8::Right,
+                '<' => Direction8::Left,
+                '^' => Direction8::Up,
+                'v' => Direction8::Down,
+                symbol => panic!("Walked off the path onto {:?} at {:?}", symbol, pos),
+            };
+            pos += dir.offset();
+            steps += 1;
+        }
+        assert_eq!(steps, 31);
+        assert_eq!(picture.cells[Vec2::new(0, 1)].colour, HEIGHT_COLOURS[0]);
+        assert_eq!(picture.cells[Vec2::new(0, 0)].colour, PATH_COLOUR);
+
+        assert!(Puzzle::draw(&Day12::PUZZLE, &read_terrain("SacE").unwrap(), &DrawOptions::default()).is_none());
+    }
+
+    #[test]
+    fn settings_change_the_movement_policy() {
+        let settings: Table = "diagonals = true\nmax_descent = 3".parse().unwrap();
+        let day = Puzzle::with_settings(&Day12::PUZZLE, &settings).unwrap();
+        assert!(day.policy.diagonals);
+        assert_eq!(day.policy.max_descent, Some(3));
+        let diagonals = Day12 { policy: MovementPolicy { diagonals: true, ..Default::default() } };
+        assert_eq!(Solver::solve(&diagonals, EXAMPLE), Ok((27.into(), 26.into())));
 
-        assert_eq!(start, Vec2D(0, 0));
-        assert_eq!(stop, Vec2D(2, 5));
+        let text = Puzzle::draw(&diagonals, &read_terrain(EXAMPLE).unwrap(), &DrawOptions::default()).unwrap().to_text();
+        assert!(text.contains(['↗', '↘', '↙', '↖']), "{}", text);
 
-        assert_eq!(terrain[start.0 as usize][start.1 as usize], 1);
-        assert_eq!(terrain[stop.0 as usize][stop.1 as usize], 26);
+        let bad = |settings: &str| Puzzle::with_settings(&Day12::PUZZLE, &settings.parse().unwrap()).unwrap_err();
+        assert_eq!(bad("diagonals = 1"), "Setting 'diagonals' isn't true or false");
+        assert_eq!(bad("max_ascent = 200"), "Setting 'max_ascent' is out of range");
+        assert_eq!(bad("step_cost = 2"), "There's no setting 'step_cost'");
     }
 }
@@ -4,46 +4,83 @@ use std::{error::Error, str::FromStr};
 use itertools::Itertools;
 use simple_error::SimpleError;
 
-pub fn run_day_13(input: String) {
-    let blocks = input.split("\n\n");
+use crate::error::ParseError;
+use crate::solver::{Answer, Puzzle};
 
-    let ans: i32 = blocks
-        .map(|block| {
-            let (packet1, packet2): (PacketData, PacketData) = block
-                .lines()
-                .map(|line| line.parse::<PacketData>().unwrap())
-                .collect_tuple()
-                .unwrap();
-            packet1 < packet2
-        })
-        .zip(1..)
-        .filter(|(right_order, _)| *right_order == true)
-        .map(|(_, index)| index)
-        .sum();
+pub struct Day13;
 
-    println!("Index sum for pairs in right order is {}", ans);
+impl Puzzle for Day13 {
+    /// The pairs of packets, in order.
+    type Parsed = Vec<(PacketData, PacketData)>;
 
-    let mut all_packets: Vec<PacketData> = input.split("\n").filter(|line| line.len() > 0).map(|line| line.parse().unwrap()).collect();
-    let driver1: PacketData = "[[2]]".parse().unwrap();
-    let driver2: PacketData = "[[6]]".parse().unwrap();
+    fn day(&self) -> u32 {
+        13
+    }
+
+    fn title(&self) -> &'static str {
+        "Distress Signal"
+    }
+
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Parsed, ParseError<'a>> {
+        input
+            .trim_end()
+            .split("\n\n")
+            .map(|block| {
+                let (line1, line2) = block
+                    .lines()
+                    .collect_tuple()
+                    .ok_or_else(|| ParseError::new(block, "Expected a pair of packets"))?;
+                Ok((parse_packet(line1)?, parse_packet(line2)?))
+            })
+            .collect()
+    }
+
+    fn part1(&self, pairs: &Self::Parsed) -> Result<Answer, SimpleError> {
+        let ans: i32 = pairs
+            .iter()
+            .map(|(packet1, packet2)| packet1 < packet2)
+            .zip(1..)
+            .filter(|(right_order, _)| *right_order)
+            .map(|(_, index)| index)
+            .sum();
+
+        Ok(ans.into())
+    }
+
+    fn part2(&self, pairs: &Self::Parsed) -> Result<Answer, SimpleError> {
+        let mut all_packets: Vec<PacketData> = pairs.iter().flat_map(|(packet1, packet2)| [packet1.clone(), packet2.clone()]).collect();
+        let driver1: PacketData = "[[2]]".parse().unwrap();
+        let driver2: PacketData = "[[6]]".parse().unwrap();
 
-    all_packets.push(driver1.clone());
-    all_packets.push(driver2.clone());
+        all_packets.push(driver1.clone());
+        all_packets.push(driver2.clone());
 
-    all_packets.sort_unstable();
+        all_packets.sort_unstable();
 
-    // now find the indices
-    let pos1 = all_packets.iter().position(|item| item == &driver1).unwrap() + 1;
-    let pos2 = all_packets.iter().position(|item| *item == driver2).unwrap() + 1;
-    let ans = pos1 * pos2;
+        // now find the indices
+        let pos1 = all_packets.iter().position(|item| item == &driver1).unwrap() + 1;
+        let pos2 = all_packets.iter().position(|item| *item == driver2).unwrap() + 1;
+        let ans = pos1 * pos2;
 
-    println!("The decoder key is {}", ans);
+        Ok(ans.into())
+    }
+}
 
+/// Parses a packet, pointing at the offending character if it isn't valid.
+fn parse_packet(line: &str) -> Result<PacketData, ParseError<'_>> {
+    let nested_data = parse(line).map_err(|e| match e {
+        json::Error::UnexpectedCharacter { column, .. } => {
+            let pos = line.char_indices().nth(column - 1).map_or(line.len(), |(pos, _)| pos);
+            ParseError::new(&line[pos..], e)
+        }
+        _ => ParseError::new(line, e),
+    })?;
 
+    nested_data.try_into().map_err(|e| ParseError::new(line, e))
 }
 
-#[derive(Debug, PartialEq, Eq, Ord, Clone)]
-enum PacketData {
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum PacketData {
     Num(i32),
     List(Vec<PacketData>),
 }
@@ -51,7 +88,7 @@ enum PacketData {
 impl From<Vec<i32>> for PacketData {
     fn from(v: Vec<i32>) -> Self {
         use PacketData::*;
-        List(v.into_iter().map(|x| Num(x)).collect())
+        List(v.into_iter().map(Num).collect())
     }
 }
 
@@ -65,7 +102,7 @@ impl FromStr for PacketData {
     type Err = Box<dyn Error>;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let nested_data = parse(s).unwrap();
+        let nested_data = parse(s)?;
 
         nested_data.try_into()
     }
@@ -77,7 +114,10 @@ impl TryFrom<json::JsonValue> for PacketData {
     fn try_from(value: json::JsonValue) -> Result<Self, Self::Error> {
         use json::JsonValue::*;
         match value {
-            Number(_) => Ok(PacketData::Num(value.as_i32().unwrap())),
+            Number(_) => value
+                .as_i32()
+                .map(PacketData::Num)
+                .ok_or_else(|| Box::new(SimpleError::new("Packets can only hold whole numbers")).into()),
             Array(items) => {
                 let pieces: Result<Vec<PacketData>, _> =
                     items.into_iter().map(|item| item.try_into()).collect();
@@ -92,32 +132,38 @@ impl TryFrom<json::JsonValue> for PacketData {
 
 impl PartialOrd for PacketData {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PacketData {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
         use itertools::EitherOrBoth::{Both, Left, Right};
         use std::cmp::Ordering::*;
         use PacketData::*;
         match (self, other) {
-            (Num(x), Num(y)) => x.partial_cmp(y),
-            (Num(x), List(_)) => List(vec![Num(*x)]).partial_cmp(other),
-            (List(_), Num(y)) => self.partial_cmp(&List(vec![Num(*y)])),
+            (Num(x), Num(y)) => x.cmp(y),
+            (Num(x), List(_)) => List(vec![Num(*x)]).cmp(other),
+            (List(_), Num(y)) => self.cmp(&List(vec![Num(*y)])),
             (List(xs), List(ys)) => {
                 let pairs = xs.iter().zip_longest(ys.iter());
                 for pair in pairs {
                     match pair {
                         Both(left, right) => {
                             if left < right {
-                                return Some(Less);
+                                return Less;
                             } else if right < left {
-                                return Some(Greater);
+                                return Greater;
                             } else {
                                 continue;
                             }
                         }
-                        Left(_) => return Some(Greater),
-                        Right(_) => return Some(Less),
+                        Left(_) => return Greater,
+                        Right(_) => return Less,
                     }
                 }
                 // List exhausted without one item smaller or greater, so they're the same
-                return Some(Equal);
+                Equal
             }
         }
     }
@@ -125,7 +171,7 @@ impl PartialOrd for PacketData {
 
 #[cfg(test)]
 mod tests {
-    use super::PacketData;
+    use super::{parse_packet, PacketData};
     use PacketData::*;
 
     #[test]
@@ -169,4 +215,12 @@ mod tests {
         let s = "[1, [2, 3]]";
         assert_eq!(lhs, s.parse().unwrap());
     }
+
+    #[test]
+    fn bad_packets_point_at_the_problem() {
+        let line = "[1,[2,x]]";
+        assert_eq!(parse_packet(line).unwrap_err().at, "x]]");
+        assert_eq!(parse_packet("[1,{}]").unwrap_err().at, "[1,{}]");
+        assert!(parse_packet("[1,2.5]").is_err());
+    }
 }
@@ -1,54 +1,63 @@
 use itertools::Itertools;
-use std::collections::HashMap;
 
-use super::lines::{get_points_on_segment, parse_paths, Path, Point};
+use super::lines::{get_points_on_segment, parse_paths, Path};
+use crate::error::ParseError;
+use crate::geometry::Vec2;
+use crate::grid::{Grid, GridView, SparseGrid};
 
 pub trait Cave {
-    fn square_at(&self, pos: &Point) -> Square;
-    fn is_out_of_bounds(&self, pos: &Point) -> bool;
-    fn mark_sand(&mut self, pos: &Point);
+    fn square_at(&self, pos: &Vec2) -> Square;
+    fn is_out_of_bounds(&self, pos: &Vec2) -> bool;
+    fn mark_sand(&mut self, pos: &Vec2);
+    /// The top left and bottom right corner of the rock and sand.
+    fn bounds(&self) -> (Vec2, Vec2);
 }
 
+#[derive(Clone)]
 pub struct VoidCave {
-    content: HashMap<Point, Square>,
+    content: SparseGrid<Square>,
     deepest_y: i32,
 }
 
 impl VoidCave {
-    pub fn parse(input: &str) -> Self {
-        let (_, paths) = parse_paths(input).unwrap();
-        Self::from_paths(paths)
+    pub fn parse(input: &str) -> Result<Self, ParseError<'_>> {
+        let paths = parse_paths(input)?;
+        Self::from_paths(paths).ok_or_else(|| ParseError::end_of_input("Expected at least one rock path"))
     }
 
-    pub fn from_paths(paths: Vec<Path>) -> Self {
-        let mut content = HashMap::new();
+    /// The cave with rock along `paths`, or `None` if there's no rock at all.
+    pub fn from_paths(paths: Vec<Path>) -> Option<Self> {
+        let mut content = SparseGrid::default();
 
-        let deepest_y = paths.iter().flatten().map(|p| p.y).max().unwrap();
+        let deepest_y = paths.iter().flatten().map(|p| p.y).max()?;
 
         for path in paths {
             for (x, y) in path.into_iter().tuple_windows() {
                 for p in get_points_on_segment(x, y) {
-                    content.insert(p, Square::Rock);
+                    content.set(p, Square::Rock);
                 }
             }
         }
 
-        VoidCave { content, deepest_y }
+        Some(VoidCave { content, deepest_y })
     }
 }
 
 impl Cave for VoidCave {
-    fn square_at(&self, pos: &Point) -> Square {
-        let square = self.content.get(pos);
-        square.cloned().unwrap_or_default()
+    fn square_at(&self, pos: &Vec2) -> Square {
+        *self.content.get(*pos).unwrap()
     }
 
-    fn is_out_of_bounds(&self, pos: &Point) -> bool {
+    fn is_out_of_bounds(&self, pos: &Vec2) -> bool {
         pos.y >= self.deepest_y
     }
 
-    fn mark_sand(&mut self, pos: &Point) {
-        self.content.insert(*pos, Square::Sand);
+    fn mark_sand(&mut self, pos: &Vec2) {
+        self.content.set(*pos, Square::Sand);
+    }
+
+    fn bounds(&self) -> (Vec2, Vec2) {
+        self.content.bounds().expect("There's rock in every cave")
     }
 }
 
@@ -65,76 +74,220 @@ impl CaveWithFloor {
 }
 
 impl Cave for CaveWithFloor {
-    fn mark_sand(&mut self, pos: &Point) {
-        self.cave.content.insert(*pos, Square::Sand);
+    fn mark_sand(&mut self, pos: &Vec2) {
+        self.cave.content.set(*pos, Square::Sand);
     }
 
-    fn is_out_of_bounds(&self, pos: &Point) -> bool {
+    fn is_out_of_bounds(&self, _pos: &Vec2) -> bool {
         false
     }
 
-    fn square_at(&self, pos: &Point) -> Square {
+    fn square_at(&self, pos: &Vec2) -> Square {
         if pos.y == self.floor_pos {
             Square::Rock
         } else {
             self.cave.square_at(pos)
         }
     }
+
+    /// Down to the floor, which goes on forever, so we only include the part under the rest.
+    fn bounds(&self) -> (Vec2, Vec2) {
+        let (min, max) = self.cave.bounds();
+        (min, Vec2::new(max.x, self.floor_pos))
+    }
+}
+
+/// The same cave as a `VoidCave` or a `CaveWithFloor`, but in a grid instead
+/// of a hash map, which makes it a lot faster. The grid starts out just big
+/// enough for the sand from one source, and grows when something lands
+/// outside it, like sand from another source or water spreading sideways.
+#[derive(Clone)]
+pub struct DenseCave {
+    squares: Grid<Square>,
+    /// Where the grid's top left corner is in the cave.
+    origin: Vec2,
+    deepest_y: i32,
+    floor_pos: Option<i32>,
+}
+
+impl DenseCave {
+    /// Sand from `source` falls into the void below the rock.
+    pub fn new(cave: &VoidCave, source: Vec2) -> Self {
+        Self::with_bottom(cave, source, None)
+    }
+
+    /// Sand from `source` lands on the floor, like in a `CaveWithFloor`.
+    pub fn with_floor(cave: &VoidCave, source: Vec2) -> Self {
+        Self::with_bottom(cave, source, Some(cave.deepest_y + 2))
+    }
+
+    fn with_bottom(cave: &VoidCave, source: Vec2, floor_pos: Option<i32>) -> Self {
+        // sand spreads by at most one square to either side for every row it falls
+        let bottom = floor_pos.unwrap_or(cave.deepest_y);
+        let spread = bottom - source.y;
+        let (rock_min, rock_max) = cave.bounds();
+        let origin = Vec2::new(rock_min.x.min(source.x - spread), rock_min.y.min(source.y));
+        let corner = Vec2::new(rock_max.x.max(source.x + spread), bottom);
+
+        let size = corner - origin + Vec2::new(1, 1);
+        let mut squares = Grid::new(size.x as usize, size.y as usize, Square::Air);
+        for (pos, &square) in cave.content.iter() {
+            squares[pos - origin] = square;
+        }
+
+        DenseCave { squares, origin, deepest_y: cave.deepest_y, floor_pos }
+    }
+
+    /// Makes room for `pos`, and as much again past it, so that sand that
+    /// keeps spreading that way doesn't copy the grid every time.
+    fn grow_to(&mut self, pos: Vec2) {
+        let size = Vec2::new(self.squares.width() as i32, self.squares.height() as i32);
+        let (old_min, old_max) = (self.origin, self.origin + size - Vec2::new(1, 1));
+        let min = Vec2::new(
+            if pos.x < old_min.x { pos.x - size.x } else { old_min.x },
+            if pos.y < old_min.y { pos.y - size.y } else { old_min.y },
+        );
+        let max = Vec2::new(
+            if pos.x > old_max.x { pos.x + size.x } else { old_max.x },
+            if pos.y > old_max.y { pos.y + size.y } else { old_max.y },
+        );
+
+        let size = max - min + Vec2::new(1, 1);
+        let mut squares = Grid::new(size.x as usize, size.y as usize, Square::Air);
+        for (at, &square) in self.squares.iter() {
+            squares[at + self.origin - min] = square;
+        }
+        self.squares = squares;
+        self.origin = min;
+    }
 }
 
-#[derive(Debug, PartialEq, Clone, Copy)]
+impl Cave for DenseCave {
+    fn square_at(&self, pos: &Vec2) -> Square {
+        if Some(pos.y) == self.floor_pos {
+            Square::Rock
+        } else {
+            self.squares.get(*pos - self.origin).copied().unwrap_or_default()
+        }
+    }
+
+    fn is_out_of_bounds(&self, pos: &Vec2) -> bool {
+        self.floor_pos.is_none() && pos.y >= self.deepest_y
+    }
+
+    fn mark_sand(&mut self, pos: &Vec2) {
+        if !self.squares.contains(*pos - self.origin) {
+            self.grow_to(*pos);
+        }
+        self.squares[*pos - self.origin] = Square::Sand;
+    }
+
+    fn bounds(&self) -> (Vec2, Vec2) {
+        let filled = self
+            .squares
+            .iter()
+            .filter(|(_, &square)| square != Square::Air)
+            .map(|(pos, _)| pos)
+            .collect_vec();
+        let (min_x, max_x) = filled.iter().map(|pos| pos.x).minmax().into_option().expect("There's rock");
+        let (min_y, max_y) = filled.iter().map(|pos| pos.y).minmax().into_option().expect("There's rock");
+
+        let min = self.origin + Vec2::new(min_x, min_y);
+        let max = self.origin + Vec2::new(max_x, max_y);
+        (min, Vec2::new(max.x, self.floor_pos.unwrap_or(max.y)))
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
 pub enum Square {
+    #[default]
     Air,
     Rock,
     Sand,
 }
 
-impl Default for Square {
-    fn default() -> Self {
-        Square::Air
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn create_cave() {
-        let p1 = Point { x: 0, y: 5 };
-        let p2 = Point { x: 10, y: 5 };
+        let p1 = Vec2::new(0, 5);
+        let p2 = Vec2::new(10, 5);
 
         let path = vec![p1, p2];
         let paths = vec![path];
 
-        let cave = VoidCave::from_paths(paths);
+        let cave = VoidCave::from_paths(paths).unwrap();
 
         use Square::*;
         for x in 0..=10 {
-            let checkpoint = Point { x: x, y: 5 };
+            let checkpoint = Vec2::new(x, 5);
             assert_eq!(cave.square_at(&checkpoint), Rock);
         }
         for x in 0..=10 {
-            let checkpoint = Point { x: x, y: 7 };
+            let checkpoint = Vec2::new(x, 7);
             assert_eq!(cave.square_at(&checkpoint), Air);
         }
 
-        assert_eq!(cave.is_out_of_bounds(&Point { x: 42, y: 5 }), true);
-        assert_eq!(cave.is_out_of_bounds(&Point { x: 42, y: 4 }), false);
+        assert_eq!(cave.bounds(), (Vec2::new(0, 5), Vec2::new(10, 5)));
+        assert!(cave.is_out_of_bounds(&Vec2::new(42, 5)));
+        assert!(!cave.is_out_of_bounds(&Vec2::new(42, 4)));
+    }
+
+    #[test]
+    fn rock_can_be_at_any_angle() {
+        let cave = VoidCave::parse("0,0 -> 3,3 -> 5,2").unwrap();
+        for rock in [(1, 1), (2, 2), (3, 3), (4, 2), (5, 2)] {
+            assert_eq!(cave.square_at(&Vec2::new(rock.0, rock.1)), Square::Rock);
+        }
+        assert_eq!(cave.square_at(&Vec2::new(1, 0)), Square::Air);
+        assert_eq!(cave.square_at(&Vec2::new(4, 3)), Square::Air);
+
+        assert!(VoidCave::from_paths(vec![]).is_none());
+        assert!(VoidCave::parse("").is_err());
     }
 
     #[test]
     fn floor_cave_tests() {
-        let p1 = Point { x: 0, y: 5 };
-        let p2 = Point { x: 10, y: 5 };
+        let p1 = Vec2::new(0, 5);
+        let p2 = Vec2::new(10, 5);
 
         let path = vec![p1, p2];
         let paths = vec![path];
 
-        let cave = VoidCave::from_paths(paths);
+        let cave = VoidCave::from_paths(paths).unwrap();
         
         let cave = CaveWithFloor::new(cave);
-        assert_eq!(cave.square_at(&Point{x: 400, y: 7}), Square::Rock);
+        assert_eq!(cave.square_at(&Vec2::new(400, 7)), Square::Rock);
+        assert_eq!(cave.bounds(), (Vec2::new(0, 5), Vec2::new(10, 7)));
+    }
+
+    #[test]
+    fn dense_caves_are_the_same_caves() {
+        let cave = VoidCave::from_paths(vec![vec![Vec2::new(0, 5), Vec2::new(10, 5), Vec2::new(10, 3)]]).unwrap();
+        let source = Vec2::new(4, 0);
+
+        let (mut dense, mut sparse) = (DenseCave::new(&cave, source), cave.clone());
+        // the last two are outside the grid the dense cave starts with
+        for sand in [Vec2::new(6, 4), Vec2::new(-8, 2), Vec2::new(17, -1)] {
+            dense.mark_sand(&sand);
+            sparse.mark_sand(&sand);
+        }
+        for x in -10..20 {
+            for y in -2..10 {
+                let pos = Vec2::new(x, y);
+                assert_eq!(dense.square_at(&pos), sparse.square_at(&pos), "{:?}", pos);
+                assert_eq!(dense.is_out_of_bounds(&pos), sparse.is_out_of_bounds(&pos));
+            }
+        }
+        assert_eq!(dense.bounds(), sparse.bounds());
+
+        let (dense, with_floor) = (DenseCave::with_floor(&cave, source), CaveWithFloor::new(cave));
+        assert_eq!(dense.square_at(&Vec2::new(-100, 7)), Square::Rock);
+        assert_eq!(dense.square_at(&Vec2::new(10, 4)), Square::Rock);
+        assert!(!dense.is_out_of_bounds(&Vec2::new(0, 6)));
+        assert_eq!(dense.bounds(), with_floor.bounds());
 
     }
 }
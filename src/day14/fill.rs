@@ -0,0 +1,42 @@
+use itertools::Itertools;
+
+use super::cave::{Cave, Square};
+use crate::geometry::Vec2;
+
+/// Fills a cave with sand from `source` all at once, instead of grain by grain,
+/// and returns how many grains came to rest.
+///
+/// Once the sand stops, a square is full if and only if it's air and sand can
+/// get there from one of the three squares above it. So we can sweep the
+/// cave row by row, keeping track of which squares in the row above got sand.
+/// That only works if every grain comes to rest, so the cave needs a floor:
+/// if the sand can reach the void, this gives up and returns `None`, leaving
+/// the sand it poured so far in the cave.
+pub fn fill_with_sand(cave: &mut impl Cave, source: Vec2) -> Option<usize> {
+    if cave.square_at(&source) != Square::Air {
+        return Some(0);
+    }
+    cave.mark_sand(&source);
+
+    let mut count = 1;
+    let mut row = vec![source.x];
+    let mut y = source.y;
+    while !row.is_empty() {
+        y += 1;
+        let mut below = row.iter().flat_map(|&x| [x - 1, x, x + 1]).collect_vec();
+        below.sort_unstable();
+        below.dedup();
+        below.retain(|&x| cave.square_at(&Vec2::new(x, y)) == Square::Air);
+        if below.iter().any(|&x| cave.is_out_of_bounds(&Vec2::new(x, y))) {
+            return None;
+        }
+
+        for &x in &below {
+            cave.mark_sand(&Vec2::new(x, y));
+        }
+        row = below;
+        count += row.len();
+    }
+
+    Some(count)
+}
@@ -1,58 +1,60 @@
+use itertools::Itertools;
 use nom::{
     bytes::complete::tag,
     character::complete::i32,
-    combinator::{map, verify},
-    multi::separated_list1,
+    combinator::map,
     sequence::separated_pair,
     IResult,
 };
-use std::ops::RangeInclusive;
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
-pub struct Point {
-    pub x: i32,
-    pub y: i32,
-}
-
-pub type Path = Vec<Point>;
-
-pub fn get_points_on_segment(point1: Point, point2: Point) -> Vec<Point> {
-    let (x1, y1) = (point1.x, point1.y);
-    let (x2, y2) = (point2.x, point2.y);
-
-    if x1 == x2 {
-        either_way_range(y1, y2)
-            .map(|y| Point { x: x1, y })
-            .collect()
-    } else if y1 == y2 {
-        either_way_range(x1, x2).map(|x| Point { x, y: y1 }).collect()
-    } else {
-        panic!("Invalid input. Successive points aren't on the same line")
+use crate::error::{parse_all, ParseError};
+use crate::geometry::Vec2;
+
+pub type Path = Vec<Vec2>;
+
+/// The squares on the straight line between two points, both included: along
+/// the axes, diagonally, or at any other angle with Bresenham's algorithm. They
+/// go from the smaller point to the larger, so that a segment covers the same
+/// squares whichever way round it's given.
+pub fn get_points_on_segment(point1: Vec2, point2: Vec2) -> Vec<Vec2> {
+    let (from, to) = if point1 <= point2 { (point1, point2) } else { (point2, point1) };
+    let (dx, dy) = ((to.x - from.x).abs(), -(to.y - from.y).abs());
+    let step = (to - from).signum();
+
+    let mut points = vec![from];
+    let (mut point, mut error) = (from, dx + dy);
+    while point != to {
+        let doubled = 2 * error;
+        if doubled >= dy {
+            error += dy;
+            point.x += step.x;
+        }
+        if doubled <= dx {
+            error += dx;
+            point.y += step.y;
+        }
+        points.push(point);
     }
+    points
 }
 
-fn either_way_range(start: i32, end: i32) -> RangeInclusive<i32> {
-    let (start, end) = if start < end {
-        (start, end)
-    } else {
-        (end, start)
-    };
-    start..=end
-}
-pub fn parse_paths(input: &str) -> IResult<&str, Vec<Path>> {
-    separated_list1(tag("\n"), path)(input)
+pub fn parse_paths(input: &str) -> Result<Vec<Path>, ParseError<'_>> {
+    input.lines().map(path).collect()
 }
 
-fn path(input: &str) -> IResult<&str, Path> {
-    let path_parser = separated_list1(tag(" -> "), pair);
-    verify(path_parser, |path: &Path| path.len() > 1)(input)
+/// A path of straight segments at any angle, like `498,4 -> 498,6 -> 496,8`.
+/// The spaces around the arrows are optional.
+fn path(line: &str) -> Result<Path, ParseError<'_>> {
+    let tokens = line.split("->").map(str::trim).collect_vec();
+    if tokens.len() < 2 {
+        return Err(ParseError::new(line, "A path needs at least two points"));
+    }
+
+    tokens.iter().map(|token| parse_all(pair, token)).collect()
 }
 
-fn pair(input: &str) -> IResult<&str, Point> {
-    map(separated_pair(number, tag(","), number), |(x, y)| Point {
-        x,
-        y,
-    })(input)
+fn pair(input: &str) -> IResult<&str, Vec2> {
+    map(separated_pair(number, tag(","), number), |(x, y)| Vec2::new(x, y))(input)
 }
 
 fn number(input: &str) -> IResult<&str, i32> {
@@ -73,40 +75,68 @@ mod tests {
     fn it_parses_a_pair() {
         let (rest, pair) = pair("123,456;").unwrap();
         assert_eq!(rest, ";");
-        assert_eq!(Point { x: 123, y: 456 }, pair);
+        assert_eq!(Vec2::new(123, 456), pair);
     }
 
     #[test]
     fn it_parses_a_path() {
         assert!(path("123,456").is_err());
         assert_eq!(
-            path("123,456 -> 789,123").unwrap().1,
-            vec![Point { x: 123, y: 456 }, Point { x: 789, y: 123 }]
+            path("123,456 -> 789,456").unwrap(),
+            vec![Vec2::new(123, 456), Vec2::new(789, 456)]
         );
+
+        assert_eq!(
+            path("123,456 -> 789,123->1,1").unwrap(),
+            vec![Vec2::new(123, 456), Vec2::new(789, 123), Vec2::new(1, 1)]
+        );
+        assert_eq!(path("123,456 -> 789;456").unwrap_err().at, ";456");
+        assert_eq!(path("123,456 -> ").unwrap_err().at, "");
     }
 
     #[test]
     fn it_parses_paths() {
-        let input = "123,456 -> 789,123\n424,456 -> 152,123";
+        let input = "123,456 -> 789,456\n424,456 -> 424,123";
         let expected = vec![
-            vec![Point { x: 123, y: 456 }, Point { x: 789, y: 123 }],
-            vec![Point { x: 424, y: 456 }, Point { x: 152, y: 123 }],
+            vec![Vec2::new(123, 456), Vec2::new(789, 456)],
+            vec![Vec2::new(424, 456), Vec2::new(424, 123)],
         ];
-        let (rest, res) = parse_paths(input).unwrap();
-        assert_eq!(rest, "");
+        let res = parse_paths(input).unwrap();
         assert_eq!(expected, res);
     }
 
     #[test]
     fn it_computes_points_on_segments() {
-        let p1 = Point { x: 0, y: 10 };
-        let p2 = Point { x: 0, y: 5 };
+        let p1 = Vec2::new(0, 10);
+        let p2 = Vec2::new(0, 5);
 
         let points = get_points_on_segment(p1, p2);
         assert_eq!(points.len(), 6);
 
         assert_eq!(p2, points[0]);
         assert_eq!(p1, points[5]);
-        assert_eq!(Point{x: 0, y: 6}, points[1]);
+        assert_eq!(Vec2::new(0, 6), points[1]);
+    }
+
+    #[test]
+    fn it_computes_points_on_diagonals() {
+        let points = get_points_on_segment(Vec2::new(3, 3), Vec2::new(0, 6));
+        assert_eq!(points, [Vec2::new(0, 6), Vec2::new(1, 5), Vec2::new(2, 4), Vec2::new(3, 3)]);
+
+        for (from, to) in [((0, 0), (4, 2)), ((10, 1), (3, 9)), ((-2, 5), (7, -4)), ((5, 5), (5, 5))] {
+            let (from, to) = (Vec2::new(from.0, from.1), Vec2::new(to.0, to.1));
+            let points = get_points_on_segment(from, to);
+            assert_eq!(points.len() as i32, from.chebyshev(&to) + 1);
+            assert_eq!(points, get_points_on_segment(to, from));
+            assert!(points.contains(&from) && points.contains(&to));
+            assert!(points.windows(2).all(|step| step[0].chebyshev(&step[1]) == 1));
+
+            // no square is more than half a square off the ideal line
+            let direction = to - from;
+            for p in &points {
+                let offset = p.x * direction.y - p.y * direction.x - (from.x * direction.y - from.y * direction.x);
+                assert!(offset.abs() as f64 / direction.chebyshev_norm().max(1) as f64 <= 0.5, "{:?}", p);
+            }
+        }
     }
 }
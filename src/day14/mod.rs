@@ -1,35 +1,111 @@
-use self::{
-    cave::{Cave, VoidCave, CaveWithFloor, Square},
-    lines::Point,
-};
+use self::cave::{Cave, VoidCave, DenseCave, Square};
+use self::fill::fill_with_sand;
+use self::render::picture;
+use self::rules::{ParticleRule, Sand};
+use itertools::Itertools;
+use simple_error::SimpleError;
+use std::collections::{BTreeMap, HashSet};
 
-mod cave;
+use crate::error::ParseError;
+use crate::geometry::Vec2;
+use crate::picture::Picture;
+use crate::search::bfs;
+use crate::solver::{Answer, DrawOptions, Puzzle};
+
+pub mod cave;
+mod fill;
 mod lines;
+mod render;
+pub mod rules;
+
+const SAND_SOURCE: Vec2 = Vec2::new(500, 0);
 
-pub fn run_day_14(input: String) {
-    let cave = VoidCave::parse(&input);
-    let sand_source = Point{x: 500, y: 0};
+pub struct Day14;
 
-    let mut sim = Simulator::new(cave, sand_source);
+impl Puzzle for Day14 {
+    /// The cave with the rock from the scan, before any sand falls.
+    type Parsed = VoidCave;
 
-    let mut steps = 0;
-    while sim.step() != SimulationStepResult::Finished {
-        steps += 1;
+    fn day(&self) -> u32 {
+        14
     }
 
-    println!("There's {} units of sand produced until it flows out.", steps);
+    fn title(&self) -> &'static str {
+        "Regolith Reservoir"
+    }
+
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Parsed, ParseError<'a>> {
+        VoidCave::parse(input)
+    }
 
-    // Let's do it again
-    let cave = VoidCave::parse(&input);
-    let cave = CaveWithFloor::new(cave);
+    fn part1(&self, cave: &Self::Parsed) -> Result<Answer, SimpleError> {
+        // the grains follow their paths more than they look up squares, so a
+        // dense cave isn't worth building here
+        let mut sim = Simulator::new(cave.clone(), SAND_SOURCE);
+
+        let mut steps = 0;
+        while sim.step() != SimulationStepResult::Finished {
+            steps += 1;
+        }
+
+        Ok(steps.into())
+    }
 
-    let mut sim = Simulator::new(cave, sand_source);
-    let mut steps = 0;
-    while sim.step() != SimulationStepResult::Finished {
-        steps += 1;
+    fn part2(&self, cave: &Self::Parsed) -> Result<Answer, SimpleError> {
+        // with a floor, the sand can be poured in all at once
+        let mut cave = DenseCave::with_floor(cave, SAND_SOURCE);
+        let count = fill_with_sand(&mut cave, SAND_SOURCE).ok_or_else(|| SimpleError::new("The sand fell through the floor"))?;
+        Ok(count.into())
     }
 
-    println!("There's {} units of sand produced until it blocks the source.", steps+1);
+    /// The cave once the sand starts falling into the abyss, or once it
+    /// covers the source if there's a floor.
+    fn draw(&self, cave: &Self::Parsed, options: &DrawOptions) -> Option<Picture> {
+        Some(match options.floor {
+            false => final_picture(cave.clone()),
+            true => final_picture(DenseCave::with_floor(cave, SAND_SOURCE)),
+        })
+    }
+
+    /// The sand a square at a time, until it starts falling into the abyss
+    /// or covers the source.
+    fn animate(&self, cave: &Self::Parsed, options: &DrawOptions, frame: &mut dyn FnMut(&Picture)) -> bool {
+        match options.floor {
+            false => animate_sand(cave.clone(), frame),
+            true => animate_sand(DenseCave::with_floor(cave, SAND_SOURCE), frame),
+        }
+        true
+    }
+}
+
+fn final_picture(cave: impl Cave) -> Picture {
+    let mut sim = Simulator::new(cave, SAND_SOURCE);
+    while sim.step() != SimulationStepResult::Finished {}
+    picture(&sim.cave, SAND_SOURCE, None)
+}
+
+fn animate_sand<C: Cave>(cave: C, frame: &mut dyn FnMut(&Picture)) {
+    let mut sim = Simulator::new(cave, SAND_SOURCE);
+    let mut show = |sim: &Simulator<C>, falling| frame(&picture(&sim.cave, SAND_SOURCE, falling));
+
+    loop {
+        // the simulation starts every grain where the one before it left
+        // the path, but it looks better if we see it fall from the source
+        for &pos in sim.sand_path() {
+            show(&sim, Some(pos));
+        }
+
+        loop {
+            match sim.fall() {
+                None => show(&sim, sim.sand_path().last().copied()),
+                Some(SimulationStepResult::NotFinished) => break,
+                Some(SimulationStepResult::Finished) => {
+                    show(&sim, None);
+                    return;
+                }
+            }
+        }
+    }
 }
 
 #[derive(Debug, PartialEq)]
@@ -38,45 +114,146 @@ pub enum SimulationStepResult {
     NotFinished,
 }
 
-struct Simulator<C: Cave> {
+/// Where particles come into the cave, one at a time.
+#[derive(Debug, Clone)]
+struct Source {
+    position: Vec2,
+    /// The squares the next particle from here will go through, as far as we
+    /// already know them: the ones the last one went through, up to where it
+    /// could have gone differently. Empty once the source is covered.
+    path: Vec<Vec2>,
+    /// A source is done once it's covered or a particle from it falls out of
+    /// the cave, like in part 1.
+    done: bool,
+    stats: SourceStats,
+}
+
+/// What became of the particles from one source.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SourceStats {
+    pub at_rest: usize,
+    /// Particles that fell out of the cave. That's at most one, because the
+    /// source is done then.
+    pub lost: usize,
+}
+
+/// The first particle that fell out of the cave.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Spill {
+    /// How many particles came to rest before it.
+    pub after: usize,
+    pub source: Vec2,
+    /// The last square it was on in the cave.
+    pub from: Vec2,
+}
+
+/// Drops particles into a cave that move by `rule`, taking turns between the
+/// sources. Every step, one particle falls all the way.
+///
+/// Besides the cave, it keeps what happened along the way, so that questions
+/// about the sand can be answered once it's done.
+pub struct Simulator<C: Cave, R: ParticleRule = Sand> {
     cave: C,
-    sand_path: Vec<Point>,
+    rule: R,
+    sources: Vec<Source>,
+    /// The source whose particle is falling.
+    current: usize,
+    /// Where each particle came to rest, in order.
+    resting: Vec<Vec2>,
+    first_spill: Option<Spill>,
 }
 
 impl<C: Cave> Simulator<C> {
-    fn new(cave: C, sand_source: Point) -> Self {
-        Self {
-            cave,
-            sand_path: vec![sand_source],
-        }
+    /// The puzzle's simulation: sand from one source.
+    pub fn new(cave: C, sand_source: Vec2) -> Self {
+        Self::with_rule(cave, Sand, &[sand_source])
+    }
+}
+
+impl<C: Cave, R: ParticleRule> Simulator<C, R> {
+    pub fn with_rule(cave: C, rule: R, sources: &[Vec2]) -> Self {
+        let sources = sources
+            .iter()
+            .map(|&position| Source {
+                position,
+                path: vec![position],
+                done: false,
+                stats: SourceStats::default(),
+            })
+            .collect();
+        Self { cave, rule, sources, current: 0, resting: vec![], first_spill: None }
     }
 
-    fn step(&mut self) -> SimulationStepResult {
+    pub fn step(&mut self) -> SimulationStepResult {
         loop {
-            let start = self.get_sand_start();
+            if let Some(result) = self.fall() {
+                return result;
+            }
+        }
+    }
 
-            let next_square = self.try_candidates(&start);
-            if let Some(next) = next_square {
-                if self.cave.is_out_of_bounds(&next) {
-                    return SimulationStepResult::Finished;
-                }
-                self.sand_path.push(next);
-            } else {
-                // couldn't find a next square from current starting pos
-                // that means we're coming to rest:
-                self.cave.mark_sand(&start);
-                if start == self.sand_path[0] {
-                    return SimulationStepResult::Finished;
-                }
-                // now we need to backtrack the path
-                self.sand_path.pop();
-                return SimulationStepResult::NotFinished
+    /// Moves the falling particle by one square. That's `None` while it keeps
+    /// falling, and the result of the step once it doesn't.
+    fn fall(&mut self) -> Option<SimulationStepResult> {
+        if self.sources.iter().all(|source| source.done) {
+            return Some(SimulationStepResult::Finished);
+        }
+
+        let start = self.get_sand_start();
+        let next_square = self.try_candidates(self.sand_path());
+        let source = &mut self.sources[self.current];
+        if let Some(next) = next_square {
+            if self.cave.is_out_of_bounds(&next) {
+                source.stats.lost += 1;
+                source.done = true;
+                let spill = Spill { after: self.resting.len(), source: source.position, from: start };
+                self.first_spill.get_or_insert(spill);
+                return Some(self.next_source());
+            }
+            source.path.push(next);
+            None
+        } else {
+            // couldn't find a next square from current starting pos
+            // that means we're coming to rest:
+            self.cave.mark_sand(&start);
+            source.stats.at_rest += 1;
+            self.resting.push(start);
+            self.backtrack(start);
+            Some(self.next_source())
+        }
+    }
+
+    /// Cuts every source's path back to before the square where a particle
+    /// came to rest, if it goes through there. The moves before that square
+    /// stay the same: squares only ever fill up, and every square a particle
+    /// passed up was for a better one that's still on the path.
+    ///
+    /// The falling particle's own path ends at that square, so looking from
+    /// the end finds it right away.
+    fn backtrack(&mut self, rest: Vec2) {
+        for source in self.sources.iter_mut().filter(|source| !source.done) {
+            if let Some(keep) = source.path.iter().rposition(|&pos| pos == rest) {
+                source.path.truncate(keep);
             }
+            source.done = source.path.is_empty();
         }
     }
 
-    fn try_candidates(&self, p: &Point) -> Option<Point> {
-        for candidate in Self::get_candidate_squares(p) {
+    /// Hands over to the next source that isn't done yet.
+    fn next_source(&mut self) -> SimulationStepResult {
+        let count = self.sources.len();
+        match (1..=count).map(|i| (self.current + i) % count).find(|&i| !self.sources[i].done) {
+            Some(next) => {
+                self.current = next;
+                SimulationStepResult::NotFinished
+            }
+            None => SimulationStepResult::Finished,
+        }
+    }
+
+    fn try_candidates(&self, path: &[Vec2]) -> Option<Vec2> {
+        let (p, from) = (path[path.len() - 1], path.len().checked_sub(2).map(|i| path[i]));
+        for candidate in self.rule.candidates(p, from) {
             if self.cave.square_at(&candidate) == Square::Air {
                 return Some(candidate)
             }
@@ -84,53 +261,391 @@ impl<C: Cave> Simulator<C> {
         None
     }
 
-    fn get_sand_start(&self) -> Point {
-        self.sand_path[self.sand_path.len() - 1]
+    fn get_sand_start(&self) -> Vec2 {
+        let path = self.sand_path();
+        path[path.len() - 1]
     }
 
-    fn get_candidate_squares<'a>(p: &'a Point) -> impl Iterator<Item = Point> + 'a {
-        [0, -1, 1].into_iter().map(|dx| Point {
-            x: p.x + dx,
-            y: p.y + 1,
-        })
+    /// Where the falling particle has been, from its source on.
+    fn sand_path(&self) -> &[Vec2] {
+        &self.sources[self.current].path
+    }
+
+    /// What became of the particles from each source, so far.
+    pub fn source_stats(&self) -> impl Iterator<Item = (Vec2, SourceStats)> + '_ {
+        self.sources.iter().map(|source| (source.position, source.stats))
+    }
+
+    /// The cave with all the particles that came to rest so far.
+    pub fn cave(&self) -> &C {
+        &self.cave
+    }
+
+    /// Where each particle came to rest, in order.
+    pub fn resting(&self) -> &[Vec2] {
+        &self.resting
+    }
+
+    pub fn first_spill(&self) -> Option<Spill> {
+        self.first_spill
+    }
+
+    /// The top of the pile in each column: the `y` of the highest particle
+    /// at rest there. Columns without any are left out.
+    pub fn pile_tops(&self) -> BTreeMap<i32, i32> {
+        let mut tops = BTreeMap::new();
+        for pos in &self.resting {
+            tops.entry(pos.x).and_modify(|top: &mut i32| *top = (*top).min(pos.y)).or_insert(pos.y);
+        }
+        tops
+    }
+
+    /// How many squares particles from the sources could get to, with the
+    /// cave the way it was before any of them came to rest, or `None` if
+    /// that's more than `limit`. Caves that don't stop the particles, like an
+    /// endless floor for water, have no end to it.
+    pub fn reachable_area(&self, limit: usize) -> Option<usize> {
+        // particles that move sideways care where they came from, so that's part of the state
+        let open = |pos: &Vec2| self.cave.square_at(pos) != Square::Rock && !self.cave.is_out_of_bounds(pos);
+        let starts = self.sources.iter().map(|source| (source.position, None));
+        let successors = |&(pos, from): &(Vec2, Option<Vec2>)| {
+            self.rule.candidates(pos, from).filter(open).map(move |next| (next, Some(pos))).collect_vec()
+        };
+
+        let mut squares = HashSet::new();
+        let too_many = |&(pos, _): &(Vec2, Option<Vec2>)| {
+            squares.insert(pos);
+            squares.len() > limit
+        };
+
+        let result = bfs(starts, successors, too_many);
+        result.goal().is_none().then_some(squares.len())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use super::cave::CaveWithFloor;
+    use super::rules::{Stone, Water};
+    use crate::random::Random;
+    use crate::timing::Stats;
+    use std::time::Instant;
+    use indoc::indoc;
     #[test]
     fn simple_simulation() {
-        let p1 = Point{x: 3, y: 5};
-        let p2 = Point{x: 10, y: 5};
+        let p1 = Vec2::new(3, 5);
+        let p2 = Vec2::new(10, 5);
 
         let path = vec![p1, p2];
         let paths = vec![path];
 
-        let cave = VoidCave::from_paths(paths);
+        let cave = VoidCave::from_paths(paths).unwrap();
 
-        let mut sim = Simulator::new(cave, Point{x: 5, y:0});
+        let mut sim = Simulator::new(cave, Vec2::new(5, 0));
 
         // do one step
         let res = sim.step();
         assert_eq!(res, SimulationStepResult::NotFinished);
 
-        assert_eq!(sim.cave.square_at(&Point{x: 5, y: 4}), Square::Sand);
+        assert_eq!(sim.cave.square_at(&Vec2::new(5, 4)), Square::Sand);
 
         sim.step();
-        assert_eq!(sim.cave.square_at(&Point{x: 4, y: 4}), Square::Sand);
+        assert_eq!(sim.cave.square_at(&Vec2::new(4, 4)), Square::Sand);
 
         sim.step();
-        assert_eq!(sim.cave.square_at(&Point{x: 6, y: 4}), Square::Sand);
+        assert_eq!(sim.cave.square_at(&Vec2::new(6, 4)), Square::Sand);
 
         sim.step();
-        assert_eq!(sim.cave.square_at(&Point{x: 5, y: 3}), Square::Sand);
+        assert_eq!(sim.cave.square_at(&Vec2::new(5, 3)), Square::Sand);
 
         // check if terminates
         while sim.step() == SimulationStepResult::NotFinished {
 
         }
-        assert!(true, "Found the end");
 
     }
+
+    /// Drops sand grain by grain until it's up to the source.
+    fn simulate_with_floor(cave: VoidCave, source: Vec2) -> (usize, CaveWithFloor) {
+        let mut sim = Simulator::new(CaveWithFloor::new(cave), source);
+        let mut steps = 1;
+        while sim.step() != SimulationStepResult::Finished {
+            steps += 1;
+        }
+        (steps, sim.cave)
+    }
+
+    /// Random rock paths, the same for the same seed.
+    fn random_cave(seed: u64) -> VoidCave {
+        let mut random = Random::new(seed);
+        let mut random = |max: u64| random.below(max) as i32;
+
+        let paths = (0..1 + random(8))
+            .map(|_| {
+                let mut point = Vec2::new(490 + random(20), 2 + random(15));
+                let mut path = vec![point];
+                for _ in 0..1 + random(4) {
+                    let length = 1 + random(6);
+                    point += match random(4) {
+                        0 => Vec2::new(length, 0),
+                        1 => Vec2::new(0, length),
+                        2 => Vec2::new(length, length),
+                        _ => Vec2::new(length, random(length as u64 + 1)),
+                    };
+                    path.push(point);
+                }
+                path
+            })
+            .collect();
+        VoidCave::from_paths(paths).unwrap()
+    }
+
+    #[test]
+    fn filling_agrees_with_the_simulation() {
+        let example = VoidCave::parse(EXAMPLE).unwrap();
+        let mut cave = CaveWithFloor::new(example.clone());
+        assert_eq!(fill_with_sand(&mut cave, SAND_SOURCE), Some(93));
+        assert_eq!(simulate_with_floor(example, SAND_SOURCE).0, 93);
+
+        for seed in 0..50 {
+            let (steps, simulated) = simulate_with_floor(random_cave(seed), SAND_SOURCE);
+            let mut filled = CaveWithFloor::new(random_cave(seed));
+            assert_eq!(fill_with_sand(&mut filled, SAND_SOURCE), Some(steps), "seed {}", seed);
+            let mut dense = DenseCave::with_floor(&random_cave(seed), SAND_SOURCE);
+            assert_eq!(fill_with_sand(&mut dense, SAND_SOURCE), Some(steps), "seed {}", seed);
+
+            // and the sand ends up in the same places
+            for x in 400..600 {
+                for y in 0..25 {
+                    let pos = Vec2::new(x, y);
+                    let (expected, actual) = (simulated.square_at(&pos), filled.square_at(&pos));
+                    assert_eq!(actual, expected, "seed {} at {:?}", seed, pos);
+                }
+            }
+        }
+    }
+
+    const EXAMPLE: &str = "498,4 -> 498,6 -> 496,6\n503,4 -> 502,4 -> 502,9 -> 494,9";
+
+    #[test]
+    fn draws_the_cave_like_the_puzzle() {
+        let cave = VoidCave::parse(EXAMPLE).unwrap();
+        let empty = indoc! {"
+            ......+...
+            ..........
+            ..........
+            ..........
+            ....#...##
+            ....#...#.
+            ..###...#.
+            ........#.
+            ........#.
+            #########."};
+        assert_eq!(picture(&cave, SAND_SOURCE, None).to_text(), empty);
+
+        let full = indoc! {"
+            ......+...
+            ..........
+            ......o...
+            .....ooo..
+            ....#ooo##
+            ...o#ooo#.
+            ..###ooo#.
+            ....oooo#.
+            .o.ooooo#.
+            #########."};
+        assert_eq!(Puzzle::draw(&Day14, &cave, &DrawOptions::default()).unwrap().to_text(), full);
+
+        let with_floor = DrawOptions { floor: true };
+        let filled = indoc! {"
+            ..........o..........
+            .........ooo.........
+            ........ooooo........
+            .......ooooooo.......
+            ......oo#ooo##o......
+            .....ooo#ooo#ooo.....
+            ....oo###ooo#oooo....
+            ...oooo.oooo#ooooo...
+            ..oooooooooo#oooooo..
+            .ooo#########ooooooo.
+            ooooo.......ooooooooo
+            #####################"};
+        assert_eq!(Puzzle::draw(&Day14, &cave, &with_floor).unwrap().to_text(), filled);
+
+        let mut frames = vec![];
+        assert!(Puzzle::animate(&Day14, &cave, &with_floor, &mut |frame| frames.push(frame.to_text())));
+        // every grain falls a frame per row from the source, 699 rows for
+        // all 93 of them, and then there's the finished cave
+        assert_eq!(frames.len(), 700);
+        assert_eq!(frames.last(), Some(&filled.to_string()));
+    }
+
+    #[test]
+    fn animation_ends_with_the_picture() {
+        let cave = VoidCave::parse(EXAMPLE).unwrap();
+        let mut frames = vec![];
+        assert!(Puzzle::animate(&Day14, &cave, &DrawOptions::default(), &mut |frame| frames.push(frame.to_text())));
+
+        // the first grain falls from the source to the rock, 8 squares down
+        assert_eq!(frames[0].lines().next(), Some("......o..."));
+        assert_eq!(frames[8].lines().nth(8), Some("......o.#."));
+        assert_eq!(frames[9].lines().nth(8), Some("......o.#."));
+        assert_eq!(frames[9].lines().next(), Some("......o..."));
+        assert_eq!(frames.last(), Some(&Puzzle::draw(&Day14, &cave, &DrawOptions::default()).unwrap().to_text()));
+    }
+
+    #[test]
+    fn statistics_of_the_example() {
+        let mut sim = Simulator::new(VoidCave::parse(EXAMPLE).unwrap(), SAND_SOURCE);
+        while sim.step() != SimulationStepResult::Finished {}
+
+        assert_eq!(sim.resting().len(), 24);
+        assert_eq!(sim.resting()[..3], [Vec2::new(500, 8), Vec2::new(499, 8), Vec2::new(501, 8)]);
+        assert_eq!(sim.resting().last(), Some(&Vec2::new(495, 8)));
+        assert_eq!(
+            sim.first_spill(),
+            Some(Spill { after: 24, source: SAND_SOURCE, from: Vec2::new(494, 8) })
+        );
+        assert_eq!(sim.cave().square_at(&Vec2::new(500, 2)), Square::Sand);
+
+        // column 497 has a grain on the rock and one on top of the rock above that
+        let tops = sim.pile_tops();
+        assert_eq!(tops, BTreeMap::from([(495, 8), (497, 5), (498, 7), (499, 3), (500, 2), (501, 3)]));
+
+        // all the squares the sand is on, and the ones it passes on the way out
+        assert_eq!(sim.reachable_area(1000), Some(69));
+        assert_eq!(sim.reachable_area(68), None);
+
+        // with a floor, the sand gets everywhere it can reach
+        let mut sim = Simulator::new(CaveWithFloor::new(VoidCave::parse(EXAMPLE).unwrap()), SAND_SOURCE);
+        assert_eq!(sim.reachable_area(1000), Some(93));
+        while sim.step() != SimulationStepResult::Finished {}
+        assert_eq!(sim.resting().len(), 93);
+        assert_eq!(sim.resting().last(), Some(&SAND_SOURCE));
+        assert_eq!(sim.first_spill(), None);
+
+        // water flows along the floor forever
+        let cave = CaveWithFloor::new(VoidCave::parse(EXAMPLE).unwrap());
+        assert_eq!(Simulator::with_rule(cave, Water, &[SAND_SOURCE]).reachable_area(10_000), None);
+    }
+
+    #[test]
+    fn sources_take_turns() {
+        let sources = [SAND_SOURCE, Vec2::new(506, 0)];
+        let cave = CaveWithFloor::new(VoidCave::parse(EXAMPLE).unwrap());
+        let mut sim = Simulator::with_rule(cave, Sand, &sources);
+        sim.step();
+        sim.step();
+        let stats = sim.source_stats().collect_vec();
+        assert_eq!(stats[0], (SAND_SOURCE, SourceStats { at_rest: 1, lost: 0 }));
+        assert_eq!(stats[1], (Vec2::new(506, 0), SourceStats { at_rest: 1, lost: 0 }));
+
+        // in the end, the sand is wherever it could get from either source
+        while sim.step() != SimulationStepResult::Finished {}
+        let mut filled = CaveWithFloor::new(VoidCave::parse(EXAMPLE).unwrap());
+        let expected = fill_with_sand(&mut filled, sources[0]).unwrap() + fill_with_sand(&mut filled, sources[1]).unwrap();
+        assert_eq!(sim.source_stats().map(|(_, stats)| stats.at_rest).sum::<usize>(), expected);
+        for x in 480..530 {
+            for y in 0..12 {
+                let pos = Vec2::new(x, y);
+                assert_eq!(sim.cave.square_at(&pos), filled.square_at(&pos), "{:?}", pos);
+            }
+        }
+
+        // the second source's sand spills past what a dense cave makes room for at first
+        let dense = DenseCave::with_floor(&VoidCave::parse(EXAMPLE).unwrap(), SAND_SOURCE);
+        let mut dense_sim = Simulator::with_rule(dense, Sand, &sources);
+        while dense_sim.step() != SimulationStepResult::Finished {}
+        for x in 480..530 {
+            for y in 0..12 {
+                let pos = Vec2::new(x, y);
+                assert_eq!(dense_sim.cave.square_at(&pos), filled.square_at(&pos), "{:?}", pos);
+            }
+        }
+    }
+
+    #[test]
+    fn other_particles() {
+        // stones pile up in a column, right up to the source
+        let mut sim = Simulator::with_rule(VoidCave::parse(EXAMPLE).unwrap(), Stone, &[SAND_SOURCE]);
+        while sim.step() != SimulationStepResult::Finished {}
+        assert_eq!(sim.source_stats().next().unwrap().1, SourceStats { at_rest: 9, lost: 0 });
+
+        // water fills a basin, and then runs over the side
+        let basin = VoidCave::parse("495,2 -> 495,6 -> 505,6 -> 505,2").unwrap();
+        let mut sim = Simulator::with_rule(basin, Water, &[SAND_SOURCE]);
+        while sim.step() != SimulationStepResult::Finished {}
+        assert_eq!(sim.source_stats().next().unwrap().1, SourceStats { at_rest: 36, lost: 1 });
+
+        let filled = indoc! {"
+            .....+.....
+            ...........
+            #ooooooooo#
+            #ooooooooo#
+            #ooooooooo#
+            #ooooooooo#
+            ###########"};
+        assert_eq!(picture(&sim.cave, SAND_SOURCE, None).to_text(), filled);
+    }
+
+    /// Times simulating and filling a big cave with either kind of storage.
+    /// Run with `cargo test --release -- --ignored --nocapture cave_storage`.
+    #[test]
+    #[ignore = "benchmark"]
+    fn benchmark_cave_storage() {
+        fn time<C: Cave>(make_cave: impl Fn() -> C, solve: impl Fn(C) -> usize) -> (usize, Stats) {
+            let mut answer = 0;
+            let samples = (0..10)
+                .map(|_| {
+                    // building the cave counts, too: the dense one has to size its grid
+                    let start = Instant::now();
+                    answer = solve(make_cave());
+                    start.elapsed()
+                })
+                .collect_vec();
+            (answer, Stats::from_samples(&samples))
+        }
+        fn simulate(cave: impl Cave) -> usize {
+            let mut sim = Simulator::new(cave, SAND_SOURCE);
+            (0..).take_while(|_| sim.step() != SimulationStepResult::Finished).count()
+        }
+        fn fill(mut cave: impl Cave) -> usize {
+            fill_with_sand(&mut cave, SAND_SOURCE).unwrap()
+        }
+
+        // shelves that catch the sand in a deep cave, hundreds of times the size of the puzzle's
+        let paths = (0..40)
+            .map(|i| {
+                let (x, y) = (400 + (i * 37) % 200, 10 + i * 25);
+                vec![Vec2::new(x, y), Vec2::new(x + 30, y), Vec2::new(x + 30, y - 3)]
+            })
+            .collect();
+        let cave = VoidCave::from_paths(paths).unwrap();
+
+        let (sparse_answer, sparse) = time(|| cave.clone(), simulate);
+        let (dense_answer, dense) = time(|| DenseCave::new(&cave, SAND_SOURCE), simulate);
+        assert_eq!(sparse_answer, dense_answer);
+        println!("Simulating {} grains: sparse {}, dense {}", dense_answer, sparse, dense);
+
+        let (sparse_answer, sparse) = time(|| CaveWithFloor::new(cave.clone()), fill);
+        let (dense_answer, dense) = time(|| DenseCave::with_floor(&cave, SAND_SOURCE), fill);
+        assert_eq!(sparse_answer, dense_answer);
+        println!("Filling in {} grains: sparse {}, dense {}", dense_answer, sparse, dense);
+    }
+
+    #[test]
+    fn filling_handles_big_caves() {
+        // a wide shelf deep down, so the sand pile is a triangle 500 rows high
+        let cave = VoidCave::from_paths(vec![vec![Vec2::new(0, 500), Vec2::new(1000, 500)]]).unwrap();
+        let mut cave = CaveWithFloor::new(cave);
+        assert_eq!(fill_with_sand(&mut cave, SAND_SOURCE), Some(500 * 500));
+        assert_eq!(cave.square_at(&Vec2::new(500, 501)), Square::Air);
+    }
+
+    #[test]
+    fn filling_a_cave_without_a_floor_gives_up() {
+        let mut cave = VoidCave::parse(EXAMPLE).unwrap();
+        assert_eq!(fill_with_sand(&mut cave, SAND_SOURCE), None);
+    }
 }
\ No newline at end of file
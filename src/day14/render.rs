@@ -0,0 +1,38 @@
+use itertools::Itertools;
+
+use super::cave::{Cave, Square};
+use crate::geometry::Vec2;
+use crate::grid::Grid;
+use crate::picture::{Cell, Picture, Rgb};
+
+const AIR_COLOUR: Rgb = [40, 40, 50];
+const ROCK_COLOUR: Rgb = [130, 120, 110];
+const SAND_COLOUR: Rgb = [220, 180, 90];
+const FALLING_COLOUR: Rgb = [255, 245, 170];
+const SOURCE_COLOUR: Rgb = [230, 30, 30];
+
+/// The cave the way the puzzle draws it: `#` for rock, `o` for sand, `.` for
+/// air and `+` for the source of the sand. It's cut down to the rock and sand
+/// there is, the source and the grain that's `falling`, if there is one.
+pub fn picture(cave: &impl Cave, source: Vec2, falling: Option<Vec2>) -> Picture {
+    let (mut min, mut max) = cave.bounds();
+    for pos in [Some(source), falling].into_iter().flatten() {
+        min = Vec2::new(min.x.min(pos.x), min.y.min(pos.y));
+        max = Vec2::new(max.x.max(pos.x), max.y.max(pos.y));
+    }
+
+    let (width, height) = ((max.x - min.x + 1) as usize, (max.y - min.y + 1) as usize);
+    let mut cells = Grid::new(width, height, Cell { symbol: '.', colour: AIR_COLOUR });
+    for pos in cells.positions().collect_vec() {
+        let at = min + pos;
+        cells[pos] = match cave.square_at(&at) {
+            _ if Some(at) == falling => Cell { symbol: 'o', colour: FALLING_COLOUR },
+            Square::Rock => Cell { symbol: '#', colour: ROCK_COLOUR },
+            Square::Sand => Cell { symbol: 'o', colour: SAND_COLOUR },
+            Square::Air if at == source => Cell { symbol: '+', colour: SOURCE_COLOUR },
+            Square::Air => continue,
+        };
+    }
+
+    Picture { cells }
+}
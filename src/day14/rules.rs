@@ -0,0 +1,76 @@
+use crate::geometry::{Direction8, Vec2};
+
+/// How particles move through the cave. Each step, a particle moves to the
+/// first of its candidate squares that's air, and comes to rest if none is.
+///
+/// The candidates may only depend on where the particle is and where it came
+/// from, and a particle must never come back to a square it has been on.
+pub trait ParticleRule {
+    /// The squares a particle at `pos` tries, in order. `from` is the square
+    /// it was on before, unless it just came out of the source.
+    fn candidates(&self, pos: Vec2, from: Option<Vec2>) -> impl Iterator<Item = Vec2>;
+}
+
+fn towards(pos: Vec2, dirs: impl IntoIterator<Item = Direction8>) -> impl Iterator<Item = Vec2> {
+    dirs.into_iter().map(move |dir| pos + dir.offset())
+}
+
+/// The puzzle's sand: straight down, or else diagonally down to the left, or else to the right.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Sand;
+
+impl ParticleRule for Sand {
+    fn candidates(&self, pos: Vec2, _from: Option<Vec2>) -> impl Iterator<Item = Vec2> {
+        towards(pos, [Direction8::Down, Direction8::DownLeft, Direction8::DownRight])
+    }
+}
+
+/// Stones that only fall straight down and stay wherever they land.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Stone;
+
+impl ParticleRule for Stone {
+    fn candidates(&self, pos: Vec2, _from: Option<Vec2>) -> impl Iterator<Item = Vec2> {
+        towards(pos, [Direction8::Down])
+    }
+}
+
+/// Water falls straight down and spreads sideways once it can't: to the left
+/// first, then to the right, and it keeps flowing the way it started until it
+/// can fall again or hits something.
+///
+/// Water on an endless floor never stops flowing, so it only suits caves
+/// where it can fall out of bounds.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Water;
+
+impl ParticleRule for Water {
+    fn candidates(&self, pos: Vec2, from: Option<Vec2>) -> impl Iterator<Item = Vec2> {
+        let sideways = match from.map(|from| pos - from) {
+            Some(flow) if flow.y == 0 => [Some(pos + flow), None],
+            _ => [Direction8::Left, Direction8::Right].map(|dir| Some(pos + dir.offset())),
+        };
+        towards(pos, [Direction8::Down]).chain(sideways.into_iter().flatten())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use itertools::Itertools;
+
+    #[test]
+    fn test_candidates() {
+        let pos = Vec2::new(5, 5);
+        assert_eq!(
+            Sand.candidates(pos, None).collect_vec(),
+            [Vec2::new(5, 6), Vec2::new(4, 6), Vec2::new(6, 6)]
+        );
+        assert_eq!(Stone.candidates(pos, Some(Vec2::new(5, 4))).collect_vec(), [Vec2::new(5, 6)]);
+
+        let after_falling = Water.candidates(pos, Some(Vec2::new(5, 4))).collect_vec();
+        assert_eq!(after_falling, [Vec2::new(5, 6), Vec2::new(4, 5), Vec2::new(6, 5)]);
+        let flowing_right = Water.candidates(pos, Some(Vec2::new(4, 5))).collect_vec();
+        assert_eq!(flowing_right, [Vec2::new(5, 6), Vec2::new(6, 5)]);
+    }
+}
@@ -1,79 +1,197 @@
 use itertools::Itertools;
+use simple_error::SimpleError;
+use toml::Table;
+
+use self::themap::SensorBeaconPair;
+use crate::error::{parse_all, ParseError};
+use crate::geometry::Vec2;
+use crate::interval::{Interval, IntervalSet};
+use crate::solver::{Answer, Puzzle};
+
+/// The numbers the puzzle picks for the real input. The example in the
+/// description uses smaller ones.
+#[derive(Debug, Clone, Copy)]
+pub struct Day15 {
+    /// The row part 1 counts the squares that can't have a beacon in.
+    pub row: i32,
+    /// Part 2 looks for the distress beacon in `0..=search_size` in both directions.
+    pub search_size: i32,
+    /// The tuning frequency is `x * frequency_multiplier + y`, which has to fit
+    /// into an answer.
+    pub frequency_multiplier: u64,
+}
+
+impl Day15 {
+    pub const PUZZLE: Day15 = Day15 { row: 2000000, search_size: 4000000, frequency_multiplier: 4000000 };
+    pub const EXAMPLE: Day15 = Day15 { row: 10, search_size: 20, ..Day15::PUZZLE };
+}
 
-use self::{themap::SensorBeaconPair, interval::IntervalSet};
+impl Puzzle for Day15 {
+    /// Each sensor with the closest beacon it found.
+    type Parsed = Vec<SensorBeaconPair>;
+
+    fn day(&self) -> u32 {
+        15
+    }
 
-pub fn run_day_15(input: String) {
+    fn title(&self) -> &'static str {
+        "Beacon Exclusion Zone"
+    }
 
-    let (_, sbs) = SensorBeaconPair::parse_lines(&input).unwrap();
-    assert_eq!(sbs.len(), 23);
-    let y_pos = 2000000;
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Parsed, ParseError<'a>> {
+        parse_all(SensorBeaconPair::parse_lines, input)
+    }
 
-    let intervals = get_intervals_for_y(&sbs, y_pos, false);
+    fn part1(&self, sbs: &Self::Parsed) -> Result<Answer, SimpleError> {
+        let y_pos = self.row;
 
-    let beacons_on_the_line = sbs.iter().map(|sb| sb.beacon)
-    .filter(|beacon| beacon.1 == y_pos).unique().count();
+        let intervals = get_intervals_for_y(sbs, y_pos, None);
 
-    let ans = intervals.len() - beacons_on_the_line;
+        let beacons_on_the_line = sbs.iter().map(|sb| sb.beacon)
+        .filter(|beacon| beacon.y == y_pos).unique().count();
 
-    println!("Not counting positions where there are beacons already, there are {} positions that cannot be beacons.", ans);
+        let ans = intervals.len() - beacons_on_the_line;
 
-    // part 2
-    for y_pos in 0..=4000000 {
-        let intervals = get_intervals_for_y(&sbs, y_pos, true);
-        if intervals.len() != 4000001 {
-            // found the y_position! now find x
-            let x_pos = intervals.gap();
-            let ans = (x_pos as u64) * 4000000 + y_pos as u64;
-            println!("Tuning frequency is {}", ans);
-            break;
-            
-        }
+        Ok(ans.into())
     }
-}
 
-fn get_intervals_for_y(sbs: &Vec<SensorBeaconPair>, y_pos: i32, truncate: bool) -> IntervalSet {
-    let mut intervals = IntervalSet::new();
+    fn part2(&self, sbs: &Self::Parsed) -> Result<Answer, SimpleError> {
+        let beacon = find_distress_beacon(sbs, self.search_size)
+            .ok_or_else(|| SimpleError::new("There's no room for the distress beacon anywhere"))?;
 
-    for sb in sbs {
-        if let Some(interval) = sb.get_y_intersect(y_pos) {
-            if !truncate {
-                intervals.add(interval);
-            } else {
-                intervals.add(interval.truncate(0, 4000000));
+        let ans = (beacon.x as u64)
+            .checked_mul(self.frequency_multiplier)
+            .and_then(|x| x.checked_add(beacon.y as u64))
+            .and_then(|frequency| i64::try_from(frequency).ok())
+            .ok_or_else(|| SimpleError::new(format!("The tuning frequency of {:?} is too big", beacon)))?;
+        Ok(ans.into())
+    }
+
+    fn with_settings(&self, settings: &Table) -> Result<Self, String> {
+        let mut day = *self;
+        for (name, value) in settings {
+            let number = value.as_integer().ok_or_else(|| format!("Setting '{}' isn't a number", name))?;
+            let out_of_range = || format!("Setting '{}' is out of range", name);
+            match name.as_str() {
+                "row" => day.row = number.try_into().map_err(|_| out_of_range())?,
+                "search_size" => day.search_size = i32::try_from(number).ok().filter(|size| *size >= 0).ok_or_else(out_of_range)?,
+                "frequency_multiplier" => day.frequency_multiplier = number.try_into().map_err(|_| out_of_range())?,
+                _ => return Err(format!("There's no setting '{}'", name)),
             }
         }
+        Ok(day)
     }
-    intervals
+}
+
+/// Finds the one square in `0..=max` in both directions that no sensor covers.
+///
+/// Usually that square is where the edges of the sensors' diamonds, or of the
+/// search area, cross, so we try those first. When none of them is free, the
+/// square is somewhere else, and we scan for it row by row.
+fn find_distress_beacon(sbs: &[SensorBeaconPair], max: i32) -> Option<Vec2> {
+    find_where_edges_cross(sbs, max).or_else(|| scan_for_distress_beacon(sbs, max))
+}
+
+/// A square in `0..=max` that no sensor covers, out of the ones where the
+/// edges cross.
+///
+/// The square has to be right next to some diamond, or the edge of the search
+/// area, on every side. Diamond edges are diagonals, so each is a line
+/// `y = x + a` or `y = -x + b`, and there are only a few crossings to try.
+/// That's not everywhere the square can be, though: it can also sit a step
+/// away from one diamond's edge while others box it in.
+fn find_where_edges_cross(sbs: &[SensorBeaconPair], max: i32) -> Option<Vec2> {
+    // the lines just outside each diamond
+    let rising = sbs.iter().flat_map(|sb| {
+        let a = sb.sensor.y - sb.sensor.x;
+        [a - sb.size() - 1, a + sb.size() + 1]
+    });
+    let falling = sbs.iter().flat_map(|sb| {
+        let b = sb.sensor.y + sb.sensor.x;
+        [b - sb.size() - 1, b + sb.size() + 1]
+    });
+    let (rising, falling): (Vec<i32>, Vec<i32>) = (rising.unique().collect(), falling.unique().collect());
+
+    let crossings = rising
+        .iter()
+        .cartesian_product(&falling)
+        .filter(|(a, b)| (*b - *a) % 2 == 0)
+        .map(|(a, b)| Vec2::new((b - a) / 2, (a + b) / 2));
+    // and where the lines meet the edges of the search area
+    let on_edges = [0, max].into_iter().flat_map(|edge| {
+        let rising = rising.iter().flat_map(move |a| [Vec2::new(edge, edge + a), Vec2::new(edge - a, edge)]);
+        let falling = falling.iter().flat_map(move |b| [Vec2::new(edge, b - edge), Vec2::new(b - edge, edge)]);
+        rising.chain(falling)
+    });
+    let corners = [Vec2::new(0, 0), Vec2::new(0, max), Vec2::new(max, 0), Vec2::new(max, max)];
+
+    crossings
+        .chain(on_edges)
+        .chain(corners)
+        .filter(|p| (0..=max).contains(&p.x) && (0..=max).contains(&p.y))
+        .find(|p| sbs.iter().all(|sb| !sb.covers(p)))
+}
+
+/// The slow way to find the distress beacon, row by row.
+fn scan_for_distress_beacon(sbs: &[SensorBeaconPair], max: i32) -> Option<Vec2> {
+    (0..=max).find_map(|y_pos| {
+        let intervals = get_intervals_for_y(sbs, y_pos, Some(max));
+        let gap = intervals.complement(Interval::new(0, max.into())).iter().next()?;
+        Some(Vec2::new(gap.low() as i32, y_pos))
+    })
+}
+
+/// What the sensors cover of row `y_pos`, cut down to `0..=max` if `truncate` is `Some(max)`.
+fn get_intervals_for_y(sbs: &[SensorBeaconPair], y_pos: i32, truncate: Option<i32>) -> IntervalSet {
+    let bounds = truncate.map(|max| Interval::new(0, max.into()));
+    sbs.iter()
+        .filter_map(|sb| sb.get_y_intersect(y_pos))
+        .filter_map(|interval| match bounds {
+            None => Some(interval),
+            Some(bounds) => interval.intersection(&bounds),
+        })
+        .collect()
 }
 
 mod themap {
     use nom::{IResult, bytes::complete::tag};
 
-    use super::{vec2d::Vec2D, interval::Interval};
+    use crate::geometry::Vec2;
+    use crate::interval::Interval;
 
     pub struct SensorBeaconPair {
-        pub sensor: Vec2D,
-        pub beacon: Vec2D,
+        pub sensor: Vec2,
+        pub beacon: Vec2,
 
         size: i32
     }
 
     impl SensorBeaconPair {
-        pub fn new(sensor: Vec2D, beacon: Vec2D) -> Self {
+        pub fn new(sensor: Vec2, beacon: Vec2) -> Self {
             let size = sensor.manhattan(&beacon);
             SensorBeaconPair{sensor, beacon, size}
         }
 
+        /// How far the sensor can see: the distance to its beacon.
+        pub fn size(&self) -> i32 {
+            self.size
+        }
+
+        /// Whether the sensor would have found a beacon at `pos`.
+        pub fn covers(&self, pos: &Vec2) -> bool {
+            self.sensor.manhattan(pos) <= self.size
+        }
+
         pub fn get_y_intersect(&self, y_pos: i32) -> Option<Interval> {
-            let y_dist = (y_pos - self.sensor.1).abs();
+            let y_dist = (y_pos - self.sensor.y).abs();
             if y_dist > self.size {
                 None
             } else {
                 //what is the intersect?
                 let left_over_for_x_dist = self.size - y_dist;
-                let min_x = self.sensor.0 - left_over_for_x_dist;
-                let max_x = self.sensor.0 + left_over_for_x_dist;
-                Some(Interval::new(min_x, max_x))
+                let min_x = self.sensor.x - left_over_for_x_dist;
+                let max_x = self.sensor.x + left_over_for_x_dist;
+                Some(Interval::new(min_x.into(), max_x.into()))
             }
         }
 
@@ -91,170 +209,99 @@ mod themap {
             let (input, _) = tag(", y=")(input)?;
             let (input, beacon_y) = nom::character::complete::i32(input)?;
             
-            Ok((input, SensorBeaconPair::new(Vec2D(sensor_x, sensor_y), Vec2D(beacon_x, beacon_y))))
+            Ok((input, SensorBeaconPair::new(Vec2::new(sensor_x, sensor_y), Vec2::new(beacon_x, beacon_y))))
         }
 
     }
 }
 
-mod vec2d {
-    use std::ops::{Sub, Add};
-
-    #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
-    pub struct Vec2D(pub i32, pub i32);
-
-    impl Vec2D {
-        pub fn norm1(&self) -> i32 {
-            self.0.abs() + self.1.abs()
-        }
-
-        pub fn manhattan(&self, other: &Self) -> i32 {
-            (*self - *other).norm1()
-        }
-    }
-
-    impl Add<Vec2D> for Vec2D {
-        type Output = Vec2D;
-
-        fn add(self, rhs: Vec2D) -> Vec2D {
-            Vec2D(self.0 + rhs.0, self.1 + rhs.1)
-        }
-    }
-
-    impl Sub<Vec2D> for Vec2D {
-        type Output = Vec2D;
-
-        fn sub(self, rhs: Vec2D) -> Vec2D {
-            Vec2D(self.0 - rhs.0, self.1 - rhs.1)
-        }
-    }
-}
+#[cfg(test)]
+mod tests {
+    use super::{themap::SensorBeaconPair, *};
+    use crate::{geometry::Vec2, solver::Solver};
+    use indoc::indoc;
+
+    const EXAMPLE: &str = indoc! {"
+        Sensor at x=2, y=18: closest beacon is at x=-2, y=15
+        Sensor at x=9, y=16: closest beacon is at x=10, y=16
+        Sensor at x=13, y=2: closest beacon is at x=15, y=3
+        Sensor at x=12, y=14: closest beacon is at x=10, y=16
+        Sensor at x=10, y=20: closest beacon is at x=10, y=16
+        Sensor at x=14, y=17: closest beacon is at x=10, y=16
+        Sensor at x=8, y=7: closest beacon is at x=2, y=10
+        Sensor at x=2, y=0: closest beacon is at x=2, y=10
+        Sensor at x=0, y=11: closest beacon is at x=2, y=10
+        Sensor at x=20, y=14: closest beacon is at x=25, y=17
+        Sensor at x=17, y=20: closest beacon is at x=21, y=22
+        Sensor at x=16, y=7: closest beacon is at x=15, y=3
+        Sensor at x=14, y=3: closest beacon is at x=15, y=3
+        Sensor at x=20, y=1: closest beacon is at x=15, y=3"};
 
-mod interval {
-    use itertools::Itertools;
+    #[test]
+    fn distress_beacon_is_where_the_edges_cross() {
+        let sbs = Day15::EXAMPLE.parse(EXAMPLE).unwrap();
+        assert_eq!(find_distress_beacon(&sbs, 20), Some(Vec2::new(14, 11)));
+        assert_eq!(scan_for_distress_beacon(&sbs, 20), Some(Vec2::new(14, 11)));
 
-    #[derive(Debug)]
-    pub struct IntervalSet {
-        intervals: Vec<Interval>,
-    }
+        // a corner of the search area, which isn't next to any diamond's corner
+        let sb = vec![SensorBeaconPair::new(Vec2::new(6, 6), Vec2::new(6, 17))];
+        assert_eq!(find_distress_beacon(&sb, 10), Some(Vec2::new(0, 0)));
 
-    impl IntervalSet {
-        pub fn new() -> Self {
-            let intervals = vec![];
-            IntervalSet { intervals }
-        }
+        let sb = vec![SensorBeaconPair::new(Vec2::new(5, 5), Vec2::new(5, 15))];
+        assert_eq!(find_distress_beacon(&sb, 10), None);
 
-        pub fn add(&mut self, interval: Interval) {
-            let intersector = self
-                .intervals
+        let sensors = |pairs: &[[i32; 4]]| {
+            pairs
                 .iter()
-                .find_position(|i| interval.intersects_or_abuts(i));
-
-            if let Some((pos, other)) = intersector {
-                let new_interval = interval.combine(other);
-                self.intervals.remove(pos);
-                self.add(new_interval);
-            } else {
-                // new interval disjoint with all other intervals so we can just add it.
-                self.intervals.push(interval);
-                // maybe this makes live easier? sort intervals in "ascending" order?
-                self.intervals.sort_unstable_by_key(|interval| interval.0);
-            }
-        }
-
-        pub fn len(&self) -> usize {
-            self.intervals.iter().map(|i| i.len()).sum()
-        }
-
-        pub fn gap(&self) -> i32 {
-            if self.intervals.len() == 2 {
-                self.intervals[0].1 + 1
-            } else {
-                todo!()
-            }
-        }
-    }
-
-    #[derive(Debug, Clone, Copy, PartialEq)]
-    pub struct Interval(i32, i32);
-
-    impl Interval {
-        pub fn new(x: i32, y: i32) -> Self {
-            if x <= y {
-                Interval(x, y)
-            } else {
-                Interval(y, x)
-            }
-        }
-
-        pub fn len(&self) -> usize {
-            (self.1 - self.0 + 1) as usize
-        }
-
-        pub fn intersects_or_abuts(&self, other: &Self) -> bool {
-            !(self.0 > other.1 + 1 || self.1 < other.0 - 1)
-        }
-
-        pub fn combine(self, other: &Self) -> Self {
-            assert!(self.intersects_or_abuts(other));
-
-            let x = std::cmp::min(self.0, other.0);
-            let y = std::cmp::max(self.1, other.1);
-
-            Interval::new(x, y)
-        }
-
-        pub fn truncate(self, x_min: i32, x_max: i32) -> Self {
-            let x_min = std::cmp::max(self.0, x_min);
-            let x_max = std::cmp::min(self.1, x_max);
-            Self::new(x_min, x_max)
-        }
-    }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::{interval::*, vec2d::Vec2D, themap::SensorBeaconPair};
-
-    #[test]
-    fn test_interval_creation() {
-        assert_eq!(Interval::new(1, 2), Interval::new(2, 1));
+                .map(|&[sx, sy, bx, by]| SensorBeaconPair::new(Vec2::new(sx, sy), Vec2::new(bx, by)))
+                .collect_vec()
+        };
+        // on the top edge, where a rising line meets it
+        let sbs = sensors(&[
+            [8, 5, 11, 5],
+            [12, 11, 14, 14],
+            [10, 8, 17, 10],
+            [1, 6, -4, 12],
+            [13, -1, 18, 0],
+            [5, 12, 4, 16],
+        ]);
+        assert_eq!(find_where_edges_cross(&sbs, 12), Some(Vec2::new(7, 0)));
+        assert_eq!(find_distress_beacon(&sbs, 12), Some(Vec2::new(7, 0)));
+
+        // inside, but a step away from the nearest diamond's edge
+        let sbs = sensors(&[
+            [1, 11, -1, 17],
+            [11, 6, 8, 2],
+            [0, 3, 0, 1],
+            [8, -1, 2, -4],
+            [10, 6, 5, 8],
+        ]);
+        assert_eq!(find_where_edges_cross(&sbs, 12), None);
+        assert_eq!(find_distress_beacon(&sbs, 12), Some(Vec2::new(3, 4)));
     }
 
     #[test]
-    fn test_interval_set_compputations() {
-        let interval = Interval::new(0, 9);
-        let mut intervals = IntervalSet::new();
-
-        intervals.add(interval);
-        assert_eq!(
-            intervals.len(),
-            interval.len(),
-            "Single interval should just get added to interval set"
-        );
-
-        intervals.add(Interval::new(13, 14));
-        assert_eq!(
-            intervals.len(),
-            12,
-            "Adding disjoint interval increases len by that amount"
-        );
-
-        intervals.add(Interval::new(3, 7));
-        assert_eq!(
-            intervals.len(),
-            12,
-            "Adding subsumed interval doesn't change length"
-        );
-
-        intervals.add(Interval::new(-5, 3));
-        assert_eq!(intervals.len(), 17);
+    fn settings_pick_the_row_and_search_area() {
+        let settings: Table = "row = 10\nsearch_size = 20".parse().unwrap();
+        let day = Puzzle::with_settings(&Day15::PUZZLE, &settings).unwrap();
+        assert_eq!((day.row, day.search_size), (Day15::EXAMPLE.row, Day15::EXAMPLE.search_size));
+        assert_eq!(day.solve(EXAMPLE), Ok((26.into(), 56000011.into())));
+
+        let bad = |settings: &str| Puzzle::with_settings(&Day15::PUZZLE, &settings.parse().unwrap()).unwrap_err();
+        assert_eq!(bad("rows = 10"), "There's no setting 'rows'");
+        assert_eq!(bad("row = \"ten\""), "Setting 'row' isn't a number");
+        assert_eq!(bad("frequency_multiplier = -1"), "Setting 'frequency_multiplier' is out of range");
+        assert_eq!(bad("search_size = -1"), "Setting 'search_size' is out of range");
+
+        let settings: Table = "row = 10\nsearch_size = 20\nfrequency_multiplier = 1000000000000000000".parse().unwrap();
+        let day = Puzzle::with_settings(&Day15::PUZZLE, &settings).unwrap();
+        assert!(day.part2(&day.parse(EXAMPLE).unwrap()).is_err());
     }
 
     #[test]
     fn test_y_intersect() {
-        let sensor = Vec2D(0, 5);
-        let beacon = Vec2D(0, 10);
+        let sensor = Vec2::new(0, 5);
+        let beacon = Vec2::new(0, 10);
         
         let sb = SensorBeaconPair::new(sensor, beacon);
 
@@ -8,24 +8,44 @@ use nom::character::complete::{alpha1, i32};
 use nom::combinator::opt;
 use nom::multi::separated_list1;
 use nom::IResult;
-use petgraph::algo::floyd_warshall;
-use petgraph::graph::Graph;
+use simple_error::SimpleError;
 
-pub fn run_day_16(input: String) {
-    let g = ProblemGraph::parse(&input);
+use crate::error::{parse_all, ParseError};
+use crate::search::bfs;
+use crate::solver::{Answer, Puzzle};
 
-    println!("There are {} relevant nodes", g.node_weights.len());
+pub struct Day16;
 
-    let ans = g.solve();
+impl Puzzle for Day16 {
+    /// The distances between the valves that are worth opening.
+    type Parsed = ProblemGraph;
 
-    println!("Best steam release is {}", ans);
+    fn day(&self) -> u32 {
+        16
+    }
+
+    fn title(&self) -> &'static str {
+        "Proboscidea Volcanium"
+    }
 
-    let ans2 = g.solve2();
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Parsed, ParseError<'a>> {
+        ProblemGraph::parse(input)
+    }
+
+    fn part1(&self, g: &Self::Parsed) -> Result<Answer, SimpleError> {
+        let ans = g.solve();
+
+        Ok(ans.into())
+    }
 
-    println!("Best answer with elephant is {}", ans2);
+    fn part2(&self, g: &Self::Parsed) -> Result<Answer, SimpleError> {
+        let ans2 = g.solve2();
+
+        Ok(ans2.into())
+    }
 }
 #[derive(Clone)]
-struct ProblemGraph {
+pub struct ProblemGraph {
     node_weights: Vec<i32>,
     dist_mat: Vec<Vec<i32>>,
     start_node: usize,
@@ -33,64 +53,63 @@ struct ProblemGraph {
 
 type MemoMap = HashMap<(usize, i32, BitSet), i32>;
 
-type MemoMap2 = HashMap<(usize, usize, i32, BitSet), i32>;
-
 impl ProblemGraph {
-    fn parse(input: &str) -> ProblemGraph {
-        let (res, line_output) = separated_list1(tag("\n"), read_line)(input).unwrap();
-
-        let mut g: Graph<(i32), ()> = Graph::new();
-
-        let mut node_ids: HashMap<String, _> = HashMap::new();
-
-        for (node, weight, _) in &line_output {
-            let node_id = g.add_node(*weight);
-            node_ids.insert(node.clone(), node_id);
-        }
-
-        for (node, _, neighbors) in &line_output {
-            let node_id = node_ids.get(node).unwrap();
-            for neighbor in neighbors {
-                let neighbor_id = node_ids.get(neighbor).unwrap();
-                g.update_edge(*node_id, *neighbor_id, ());
-            }
-        }
-
-        let all_pairs_paths = floyd_warshall(&g, |_| 1).unwrap();
+    fn parse(input: &str) -> Result<ProblemGraph, ParseError<'_>> {
+        let lines = input.lines().collect_vec();
+        let line_output: Vec<LineOutput> = lines
+            .iter()
+            .map(|line| parse_all(read_line, line))
+            .collect::<Result<_, _>>()?;
 
-        let relevant_node_names: Vec<String> = line_output
+        let node_ids: HashMap<&str, usize> = line_output
             .iter()
-            .filter(|(name, weight, _)| name == "AA" || *weight > 0)
-            .map(|(node, _, _)| node.clone())
+            .enumerate()
+            .map(|(id, (node, _, _))| (node.as_str(), id))
             .collect();
 
-        let relevant_node_idxs = relevant_node_names
+        let mut tunnels: Vec<Vec<usize>> = vec![];
+        for ((_, _, neighbors), line) in line_output.iter().zip(&lines) {
+            let neighbor_ids = neighbors
+                .iter()
+                .map(|neighbor| {
+                    node_ids.get(neighbor.as_str()).copied().ok_or_else(|| {
+                        let at = &line[line.rfind(neighbor.as_str()).unwrap()..];
+                        ParseError::new(at, format!("There's no valve {}", neighbor))
+                    })
+                })
+                .collect::<Result<_, _>>()?;
+            tunnels.push(neighbor_ids);
+        }
+
+        let relevant_node_idxs = line_output
             .iter()
-            .map(|name| node_ids.get(name).unwrap())
-            .cloned()
+            .positions(|(name, weight, _)| name == "AA" || *weight > 0)
             .collect_vec();
+
+        // a search from each relevant valve gives the distances to all the others
         let mut weights = vec![];
         let mut dist_mat = vec![];
-        for (i, idx_i) in relevant_node_idxs.iter().enumerate() {
-            weights.push(*g.node_weight(*idx_i).unwrap());
-            dist_mat.push(vec![]);
-            for (_j, idx_j) in relevant_node_idxs.iter().enumerate() {
-                dist_mat[i].push(*all_pairs_paths.get(&(*idx_i, *idx_j)).unwrap());
-            }
+        for &idx_i in &relevant_node_idxs {
+            weights.push(line_output[idx_i].1);
+            let distances = bfs([idx_i], |&node| tunnels[node].iter().copied(), |_| false);
+            dist_mat.push(
+                relevant_node_idxs
+                    .iter()
+                    .map(|idx_j| distances.distance(idx_j).map_or(i32::MAX, |d| d as i32))
+                    .collect(),
+            );
         }
 
-        // the start node is the one with 0 flow. All others have been removed!
-        let start_node = weights
+        let start_node = relevant_node_idxs
             .iter()
-            .find_position(|weight| **weight == 0)
-            .unwrap()
-            .0;
+            .position(|&idx| line_output[idx].0 == "AA")
+            .ok_or_else(|| ParseError::new(input, "There's no valve AA to start from"))?;
 
-        ProblemGraph {
+        Ok(ProblemGraph {
             node_weights: weights,
             dist_mat,
             start_node,
-        }
+        })
     }
 
     fn solve(&self) -> i32 {
@@ -101,7 +120,6 @@ impl ProblemGraph {
                 available_nodes.insert(node);
             }
         }
-        assert_eq!(available_nodes.len(), 15);
         let time = 30;
 
         self.find_max(&mut memo, self.start_node, time, available_nodes)
@@ -116,10 +134,10 @@ impl ProblemGraph {
         }
 
         let mut best_overall = 0;
-        for el_subset_len in 1..(available_nodes.len()/2) {
+        for el_subset_len in 1..=(available_nodes.len() / 2) {
             for el_items in available_nodes.iter().combinations(el_subset_len) {
-                let mut el_nodes = BitSet::from_iter(el_items);
-                let mut my_nodes: BitSet<u32> = BitSet::from_iter(available_nodes.difference(&el_nodes));
+                let el_nodes = BitSet::from_iter(el_items);
+                let my_nodes: BitSet<u32> = BitSet::from_iter(available_nodes.difference(&el_nodes));
 
                 let mut el_memo = HashMap::new();
                 let mut my_memo = HashMap::new();
@@ -222,7 +240,7 @@ mod tests {
         let (input, flow_rate) = read_flow_rate(input).unwrap();
         assert_eq!(flow_rate, 22);
 
-        let (input, tunnels) = read_tunnel_list(input).unwrap();
+        let (input, _) = read_tunnel_list(input).unwrap();
         assert_eq!(input, "");
 
         let input = "Valve AA has flow rate=22; tunnels lead to valves BB, CC\nSome more stuff";
@@ -235,7 +253,7 @@ mod tests {
     #[test]
     fn test_graph_reading() {
         let input = "Valve AA has flow rate=0; tunnels lead to valves BB, CC\nValve BB has flow rate=15; tunnel leads to valve AA\nValve CC has flow rate=12; tunnel leads to valve AA";
-        let g = ProblemGraph::parse(input);
+        let g = ProblemGraph::parse(input).unwrap();
 
         assert_eq!(g.node_weights, vec![0, 15, 12]);
         assert_eq!(
@@ -244,5 +262,10 @@ mod tests {
         );
 
         assert_eq!(g.start_node, 0); // not actually sure if that's stable
+
+        let unknown_valve = input.replace("valve AA", "valve DD");
+        assert_eq!(ProblemGraph::parse(&unknown_valve).err().unwrap().at, "DD");
+        let bad_rate = input.replace("rate=15", "rate=lots");
+        assert_eq!(ProblemGraph::parse(&bad_rate).err().unwrap().at, "lots; tunnel leads to valve AA");
     }
 }
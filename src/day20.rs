@@ -2,39 +2,79 @@ use std::ops::Index;
 
 use itertools::Itertools;
 
-pub fn run_day_20(input: String) {
-    
-    let numbers: Vec<i64> = input.lines().map(|l| l.parse().unwrap()).collect();
-    //let numbers = vec![1, 2, -3, 3, -2, 0, 4];
+use simple_error::SimpleError;
 
-    let mut tagged_numbers: Vec<TaggedNumber> = numbers.into_iter()
-        .enumerate()
-        .map(|x| x.into())
-        .collect();
+use crate::error::{parse_lines, ParseError};
+use crate::solver::{Answer, Puzzle};
 
-    let mut tagged_numbers_1: CircularVec = tagged_numbers.clone().into();
-    
-    tagged_numbers_1.mix_once();
+pub struct Day20;
+
+const DECRYPTION_KEY: i64 = 811589153;
+
+impl Puzzle for Day20 {
+    /// The numbers of the encrypted file, tagged with their original position.
+    type Parsed = Vec<TaggedNumber>;
+
+    fn day(&self) -> u32 {
+        20
+    }
+
+    fn title(&self) -> &'static str {
+        "Grove Positioning System"
+    }
+
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Parsed, ParseError<'a>> {
+        read_tagged_numbers(input)
+    }
+
+    fn part1(&self, tagged_numbers: &Self::Parsed) -> Result<Answer, SimpleError> {
+        let mut tagged_numbers_1: CircularVec = tagged_numbers.clone().into();
+
+        tagged_numbers_1.mix_once();
+
+        let zero_pos = tagged_numbers_1.storage.iter().find_position(|el| el.val == 0).unwrap().0;
+        let ans: i64 = [1000, 2000, 3000].map(|idx| zero_pos as i64 + idx).map(|idx| tagged_numbers_1[idx].val).into_iter().sum();
 
-    let zero_pos = tagged_numbers_1.storage.iter().find_position(|el| el.val == 0).unwrap().0;
-    let ans: i64 = [1000, 2000, 3000].map(|idx| zero_pos as i64 + idx).map(|idx| tagged_numbers_1[idx].val).into_iter().sum();
+        Ok(ans.into())
+    }
+
+    fn part2(&self, tagged_numbers: &Self::Parsed) -> Result<Answer, SimpleError> {
+        let mut tagged_numbers = tagged_numbers.clone();
 
-    println!("The answer is {}", ans);
+        for x in tagged_numbers.iter_mut() {
+            x.val = x.val.checked_mul(DECRYPTION_KEY)
+                .ok_or_else(|| SimpleError::new(format!("{} is too big to decrypt", x.val)))?;
+        }
+        let mut tagged_numbers_2: CircularVec = tagged_numbers.into();
+        for _ in 0..10 {
+            tagged_numbers_2.mix_once();
+        }
+        let zero_pos = tagged_numbers_2.storage.iter().find_position(|el| el.val == 0).unwrap().0;
+        let ans: i64 = [1000, 2000, 3000].map(|idx| zero_pos as i64 + idx).map(|idx| tagged_numbers_2[idx].val).into_iter().sum();
 
-    tagged_numbers.iter_mut().for_each(|x| x.val *= 811589153);
-    let mut tagged_numbers_2: CircularVec = tagged_numbers.into();
-    for _ in 0..10 {
-        tagged_numbers_2.mix_once();
+        Ok(ans.into())
     }
-    let zero_pos = tagged_numbers_2.storage.iter().find_position(|el| el.val == 0).unwrap().0;
-    let ans: i64 = [1000, 2000, 3000].map(|idx| zero_pos as i64 + idx).map(|idx| tagged_numbers_2[idx].val).into_iter().sum();
+}
 
-    println!("The answer is {}", ans);
+/// Reads one number per line. The grove coordinates count from the 0, so there
+/// has to be one, and a number can only move if there's another one to pass.
+fn read_tagged_numbers(input: &str) -> Result<Vec<TaggedNumber>, ParseError<'_>> {
+    let numbers: Vec<i64> = parse_lines(input, str::parse)?;
+    if numbers.len() < 2 {
+        return Err(ParseError::end_of_input("There have to be at least two numbers"));
+    }
+    if !numbers.contains(&0) {
+        return Err(ParseError::new(input, "There's no 0 in the list"));
+    }
 
+    Ok(numbers.into_iter()
+        .enumerate()
+        .map(|x| x.into())
+        .collect())
 }
 
 #[derive(PartialEq, Clone, Copy)]
-struct TaggedNumber {
+pub struct TaggedNumber {
     val: i64,
     original_pos: usize,
 }
@@ -107,3 +147,20 @@ impl Index<i64> for CircularVec {
         &self.storage[index]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn one_number_is_not_enough() {
+        assert!(read_tagged_numbers("0").is_err());
+        assert!(read_tagged_numbers("0\n1").is_ok());
+    }
+
+    #[test]
+    fn decrypting_checks_for_overflow() {
+        let numbers = read_tagged_numbers("0\n9223372036854775807").unwrap();
+        assert!(Day20.part2(&numbers).is_err());
+    }
+}
@@ -7,6 +7,12 @@ pub struct CPUtracker {
     xs: Vec<i32>
 }
 
+impl Default for CPUtracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl CPUtracker {
     pub fn new() -> Self {
         CPUtracker { xs: vec![1] }
@@ -52,7 +58,7 @@ impl FromStr for Instruction {
             Ok(Instruction::NOOP)
         }
         else if let Some((_, num_as_str)) = regex_captures!(r"^addx (-?\d+)$", s) {
-            let num: i32 = num_as_str.parse().unwrap();
+            let num: i32 = num_as_str.parse()?;
             Ok(Instruction::ADDX(num))
         }
         else{
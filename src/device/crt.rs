@@ -17,7 +17,7 @@ pub fn render<I: IntoIterator<Item=i32>>(input: I) -> String {
         });
         chars.extend(chars_iter);
         row = (&mut iter).take(40).collect_vec();
-        if row.len() > 0 {
+        if row.len() == 40 {
             chars.push('\n');
         }
     }
@@ -33,7 +33,7 @@ mod tests {
     fn render_single_line() {
         use std::iter;
 
-        let input = iter::repeat(5).take(40);
+        let input = iter::repeat_n(5, 40);
 
         let output = render(input);
 
@@ -44,7 +44,7 @@ mod tests {
     fn render_two_lines() {
         use std::iter;
 
-        let input = iter::repeat(5).take(80);
+        let input = iter::repeat_n(5, 80);
 
         let output = render(input);
 
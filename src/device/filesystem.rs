@@ -3,6 +3,8 @@ use std::{collections::HashMap, str::FromStr};
 use lazy_regex::regex_captures;
 use simple_error::SimpleError;
 
+use crate::error::ParseError;
+
 #[derive(Debug, PartialEq)]
 enum TerminalOutput {
     CommandCDRoot,
@@ -25,33 +27,33 @@ impl FromStr for TerminalOutput {
 
         if s.starts_with('$') {
             if s == "$ ls" {
-                return Ok(TerminalOutput::CommandLS);
+                Ok(TerminalOutput::CommandLS)
             } else if s == "$ cd /" {
-                return Ok(TerminalOutput::CommandCDRoot);
+                Ok(TerminalOutput::CommandCDRoot)
             } else if s == "$ cd .." {
-                return Ok(TerminalOutput::CommandCDUp);
+                Ok(TerminalOutput::CommandCDUp)
             } else {
-                let (_, name) = regex_captures!(r"^\$ cd (\w+)$", s).ok_or(SimpleError::new(
+                let (_, name) = regex_captures!(r"^\$ cd (\w+)$", s).ok_or_else(|| SimpleError::new(
                     "Couldn't extract directory name from cd command",
                 ))?;
-                return Ok(TerminalOutput::CommandCDSSub(name.to_string()));
+                Ok(TerminalOutput::CommandCDSSub(name.to_string()))
             }
         } else if s.starts_with("dir") {
-            let (_, dir_name) = regex_captures!(r"^dir (\w+)$", s).ok_or(SimpleError::new(
+            let (_, dir_name) = regex_captures!(r"^dir (\w+)$", s).ok_or_else(|| SimpleError::new(
                 "Couldn't extract directory name from dir output",
             ))?;
-            return Ok(TerminalOutput::DirectoryEntry(dir_name.to_string()));
+            Ok(TerminalOutput::DirectoryEntry(dir_name.to_string()))
         } else {
             // Try to match the file entry
             let (_, file_size_str, file_name) = regex_captures!(r"^(\d+) ([\w\.]+)$", s)
-                .ok_or(SimpleError::new("Couldn't parse file entry"))?;
-            let file_size: u64 = file_size_str.parse().unwrap(); // This shouldn't panic if the regex matched
-            return Ok(TerminalOutput::FileEntry(file_size, file_name.to_string()));
+                .ok_or_else(|| SimpleError::new("Couldn't parse file entry"))?;
+            let file_size: u64 = file_size_str.parse().map_err(SimpleError::from)?;
+            Ok(TerminalOutput::FileEntry(file_size, file_name.to_string()))
         }
     }
 }
 
-fn compute_full_path(active_dirs: &Vec<String>, dirname: &str) -> String {
+fn compute_full_path(active_dirs: &[String], dirname: &str) -> String {
     if active_dirs.len() == 1 {
         return format!("/{}", dirname).to_string();
     }
@@ -107,21 +109,21 @@ impl ParseHelper {
     }
 
     fn move_down(&mut self, dirname: &str) {
-        let dirname = compute_full_path(&self.active_dirs, &dirname);
+        let dirname = compute_full_path(&self.active_dirs, dirname);
         self.active_dirs.push(dirname);
     }
 }
 
-pub fn parse_terminal_output_for_dir_sizes(output: &str) -> HashMap<String, u64> {
+pub fn parse_terminal_output_for_dir_sizes(output: &str) -> Result<HashMap<String, u64>, ParseError<'_>> {
     let mut helper = ParseHelper::new();
 
     for line in output.lines() {
-        let output: TerminalOutput = line.parse().unwrap();
+        let output: TerminalOutput = line.parse().map_err(|e| ParseError::new(line, e))?;
 
         helper.process_output(output);
     }
 
-    helper.dir_sizes
+    Ok(helper.dir_sizes)
 }
 
 #[cfg(test)]
@@ -155,6 +157,9 @@ mod tests {
             let expected = TerminalOutput::FileEntry(1234, filename.to_string());
             assert_eq!(output, expected);
         }
+
+        assert!("99999999999999999999 foo".parse::<TerminalOutput>().is_err());
+        assert!("$ rm -rf /".parse::<TerminalOutput>().is_err());
     }
 
     #[test]
@@ -188,7 +193,7 @@ mod tests {
             7214296 k"
         );
 
-        let dir_sizes = parse_terminal_output_for_dir_sizes(TEST_INPUT);
+        let dir_sizes = parse_terminal_output_for_dir_sizes(TEST_INPUT).unwrap();
 
         assert_eq!(dir_sizes.get("/a/e"), Some(&584));
         assert_eq!(dir_sizes.get("/a"), Some(&94853));
@@ -201,7 +206,7 @@ mod tests {
         let active_dirs = vec!["/".to_string(), "/b".to_string()];
         let dirname = "c";
 
-        let result = compute_full_path(&active_dirs, &dirname);
+        let result = compute_full_path(&active_dirs, dirname);
         assert_eq!(result, "/b/c".to_string());
     }
 }
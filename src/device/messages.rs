@@ -1,12 +1,12 @@
-pub fn find_start_of_packet_marker<'a, S: IntoIterator<Item = char>>(stream: S) -> Option<usize> {
+pub fn find_start_of_packet_marker<S: IntoIterator<Item = char>>(stream: S) -> Option<usize> {
     find_start_of_marker(stream, 4)
 }
 
-pub fn find_start_of_message_marker<'a, S: IntoIterator<Item = char>>(stream: S) -> Option<usize> {
+pub fn find_start_of_message_marker<S: IntoIterator<Item = char>>(stream: S) -> Option<usize> {
     find_start_of_marker(stream, 14)
 }
 
-fn find_start_of_marker<'a, S: IntoIterator<Item = char>>(
+fn find_start_of_marker<S: IntoIterator<Item = char>>(
     stream: S,
     size: usize,
 ) -> Option<usize> {
@@ -15,11 +15,7 @@ fn find_start_of_marker<'a, S: IntoIterator<Item = char>>(
 
     let mut chars = stream.into_iter();
     while letters.len() != size {
-        let next_letter = chars.next();
-        if next_letter.is_none() {
-            return None;
-        }
-        let next_letter = next_letter.unwrap();
+        let next_letter = chars.next()?;
 
         if !letters.contains(&next_letter) {
             letters.push(next_letter);
@@ -0,0 +1,173 @@
+use std::{error::Error, fmt::Display};
+
+/// Something in the puzzle input that isn't the way the puzzle says it should be.
+///
+/// `at` is the slice of the input where the problem starts. That's all a parser
+/// has to know: the runner, which has the whole input, turns it into a line and
+/// column for the `AocError`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError<'a> {
+    pub at: &'a str,
+    pub reason: String,
+}
+
+impl<'a> ParseError<'a> {
+    pub fn new(at: &'a str, reason: impl Display) -> Self {
+        ParseError { at, reason: reason.to_string() }
+    }
+
+    /// For when the input stops before we've got everything we need.
+    pub fn end_of_input(reason: impl Display) -> Self {
+        ParseError::new("", reason)
+    }
+}
+
+impl<'a> From<nom::Err<nom::error::Error<&'a str>>> for ParseError<'a> {
+    fn from(e: nom::Err<nom::error::Error<&'a str>>) -> Self {
+        match e {
+            nom::Err::Incomplete(_) => ParseError::end_of_input("Unexpected end of input"),
+            nom::Err::Error(e) | nom::Err::Failure(e) => ParseError::new(
+                e.input,
+                format!("Unexpected text (expected {})", e.code.description()),
+            ),
+        }
+    }
+}
+
+/// Runs a nom `parser` over all of `input`. Text it leaves over is an error, too.
+pub fn parse_all<'a, T>(
+    mut parser: impl FnMut(&'a str) -> nom::IResult<&'a str, T>,
+    input: &'a str,
+) -> Result<T, ParseError<'a>> {
+    let (rest, parsed) = parser(input)?;
+    if rest.trim().is_empty() {
+        Ok(parsed)
+    } else {
+        Err(ParseError::new(rest, "Unexpected text"))
+    }
+}
+
+/// Parses each line of `input` on its own. Errors without a more precise
+/// location point at the start of their line.
+pub fn parse_lines<'a, T, E: Display>(
+    input: &'a str,
+    parse_line: impl Fn(&'a str) -> Result<T, E>,
+) -> Result<Vec<T>, ParseError<'a>> {
+    input
+        .lines()
+        .map(|line| parse_line(line).map_err(|e| ParseError::new(line, e)))
+        .collect()
+}
+
+/// Something that went wrong solving a day.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AocError {
+    /// The input is malformed. `line` and `column` count from 1, and `text` is
+    /// the whole offending line.
+    Parse {
+        day: u32,
+        line: usize,
+        column: usize,
+        text: String,
+        reason: String,
+    },
+    /// The input is fine, but the puzzle has no answer for it.
+    NoAnswer { day: u32, part: u8, reason: String },
+    /// The answer sheet has settings for the day that it can't use.
+    Settings { day: u32, reason: String },
+}
+
+impl AocError {
+    /// Pins down where in `input` the `error` happened.
+    pub fn parse(day: u32, input: &str, error: ParseError) -> Self {
+        // `at` should be a slice of `input`. Anything else, like the empty
+        // string of `end_of_input`, gets reported at the end.
+        let start = input.as_ptr() as usize;
+        let offset = (error.at.as_ptr() as usize)
+            .checked_sub(start)
+            .filter(|offset| *offset <= input.len() && input.is_char_boundary(*offset))
+            .unwrap_or(input.len());
+
+        let before = &input[..offset];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        let text = input[line_start..].lines().next().unwrap_or("");
+
+        AocError::Parse {
+            day,
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            text: text.to_string(),
+            reason: error.reason,
+        }
+    }
+}
+
+impl Display for AocError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AocError::Parse { day, line, column, text, reason } => {
+                writeln!(f, "Day {}, line {}, column {}: {}", day, line, column, reason)?;
+                writeln!(f, "    {}", text)?;
+                write!(f, "    {:>column$}", "^", column = column)
+            }
+            AocError::NoAnswer { day, part, reason } => {
+                write!(f, "Day {} part {} has no answer: {}", day, part, reason)
+            }
+            AocError::Settings { day, reason } => write!(f, "Day {} has bad settings: {}", day, reason),
+        }
+    }
+}
+
+impl Error for AocError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn locates_errors_in_the_input() {
+        let input = "1-2,3-4\n5-6,x-8\n";
+        let error = ParseError::new(&input[12..], "'x' isn't a number");
+
+        let error = AocError::parse(4, input, error);
+        assert_eq!(
+            error,
+            AocError::Parse {
+                day: 4,
+                line: 2,
+                column: 5,
+                text: "5-6,x-8".to_string(),
+                reason: "'x' isn't a number".to_string()
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            "Day 4, line 2, column 5: 'x' isn't a number\n    5-6,x-8\n        ^"
+        );
+    }
+
+    #[test]
+    fn errors_outside_the_input_are_at_the_end() {
+        let input = "abc\ndef";
+        let error = AocError::parse(1, input, ParseError::end_of_input("Need more"));
+        assert!(matches!(error, AocError::Parse { line: 2, column: 4, .. }), "{:?}", error);
+    }
+
+    #[test]
+    fn parse_lines_points_at_the_failing_line() {
+        let input = "1\n2\nthree\n4";
+        let error = parse_lines(input, |line| line.parse::<i32>()).unwrap_err();
+        assert_eq!(error.at, "three");
+        assert_eq!(error.at.as_ptr(), input[4..].as_ptr());
+
+        assert_eq!(parse_lines("1\n2", |line| line.parse::<i32>()), Ok(vec![1, 2]));
+    }
+
+    #[test]
+    fn parse_all_rejects_leftovers() {
+        let number = nom::character::complete::i32;
+        assert_eq!(parse_all(number, "42\n"), Ok(42));
+        assert_eq!(parse_all(number, "42 apples").unwrap_err().at, " apples");
+        assert_eq!(parse_all(number, "apples").unwrap_err().at, "apples");
+    }
+}
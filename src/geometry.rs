@@ -0,0 +1,305 @@
+//! Points, vectors and directions for the puzzles that happen on a grid or in space.
+//!
+//! On grids, `x` is the column and `y` the row, so `y` grows downwards: `Up` is
+//! `(0, -1)`. That's how the puzzle texts draw their maps.
+
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+use num::{Signed, ToPrimitive};
+
+/// A point or vector in the plane.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Vec2<T = i32> {
+    pub x: T,
+    pub y: T,
+}
+
+/// A point or vector in space.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Vec3<T = i32> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+impl<T> Vec2<T> {
+    pub const fn new(x: T, y: T) -> Self {
+        Vec2 { x, y }
+    }
+}
+
+impl<T> Vec3<T> {
+    pub const fn new(x: T, y: T, z: T) -> Self {
+        Vec3 { x, y, z }
+    }
+}
+
+/// Arithmetic and norms, the same for every dimension.
+macro_rules! impl_vec {
+    ($name:ident { $($field:ident),+ }) => {
+        impl<T: Signed + PartialOrd + Copy> $name<T> {
+            /// The L1 norm: the number of steps along the axes to get here.
+            pub fn manhattan_norm(&self) -> T {
+                T::zero() $(+ self.$field.abs())+
+            }
+
+            /// The L∞ norm: the number of steps to get here when diagonal
+            /// steps are allowed.
+            pub fn chebyshev_norm(&self) -> T {
+                let mut norm = T::zero();
+                $(
+                    if self.$field.abs() > norm {
+                        norm = self.$field.abs();
+                    }
+                )+
+                norm
+            }
+
+            pub fn manhattan(&self, other: &Self) -> T {
+                (*self - *other).manhattan_norm()
+            }
+
+            pub fn chebyshev(&self, other: &Self) -> T {
+                (*self - *other).chebyshev_norm()
+            }
+
+            /// Each coordinate as -1, 0 or 1: the single step in the direction of this vector.
+            pub fn signum(&self) -> Self {
+                $name { $($field: self.$field.signum()),+ }
+            }
+        }
+
+        impl<T: ToPrimitive + Copy> $name<T> {
+            /// The L2 norm, the length as the crow flies.
+            pub fn euclidean_norm(&self) -> f64 {
+                (0.0 $(+ self.$field.to_f64().unwrap().powi(2))+).sqrt()
+            }
+        }
+
+        impl<T: Sub<Output = T> + ToPrimitive + Copy> $name<T> {
+            pub fn euclidean(&self, other: &Self) -> f64 {
+                (*self - *other).euclidean_norm()
+            }
+        }
+
+        impl<T: Add<Output = T>> Add for $name<T> {
+            type Output = Self;
+
+            fn add(self, rhs: Self) -> Self {
+                $name { $($field: self.$field + rhs.$field),+ }
+            }
+        }
+
+        impl<T: Sub<Output = T>> Sub for $name<T> {
+            type Output = Self;
+
+            fn sub(self, rhs: Self) -> Self {
+                $name { $($field: self.$field - rhs.$field),+ }
+            }
+        }
+
+        impl<T: Neg<Output = T>> Neg for $name<T> {
+            type Output = Self;
+
+            fn neg(self) -> Self {
+                $name { $($field: -self.$field),+ }
+            }
+        }
+
+        /// Scaling by a number.
+        impl<T: Mul<Output = T> + Copy> Mul<T> for $name<T> {
+            type Output = Self;
+
+            fn mul(self, rhs: T) -> Self {
+                $name { $($field: self.$field * rhs),+ }
+            }
+        }
+
+        impl<T: AddAssign> AddAssign for $name<T> {
+            fn add_assign(&mut self, rhs: Self) {
+                $(self.$field += rhs.$field;)+
+            }
+        }
+
+        impl<T: SubAssign> SubAssign for $name<T> {
+            fn sub_assign(&mut self, rhs: Self) {
+                $(self.$field -= rhs.$field;)+
+            }
+        }
+    };
+}
+
+impl_vec!(Vec2 { x, y });
+impl_vec!(Vec3 { x, y, z });
+
+impl Vec2<i32> {
+    /// The four points next to this one, clockwise from the one above.
+    pub fn neighbours4(self) -> impl Iterator<Item = Self> {
+        Direction::ALL.into_iter().map(move |dir| self + dir.offset())
+    }
+
+    /// The eight points around this one, clockwise from the one above.
+    pub fn neighbours8(self) -> impl Iterator<Item = Self> {
+        Direction8::ALL.into_iter().map(move |dir| self + dir.offset())
+    }
+}
+
+/// The four directions along the axes of a grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    /// Clockwise, starting with `Up`.
+    pub const ALL: [Direction; 4] = [Direction::Up, Direction::Right, Direction::Down, Direction::Left];
+
+    pub fn offset(self) -> Vec2 {
+        match self {
+            Direction::Up => Vec2::new(0, -1),
+            Direction::Right => Vec2::new(1, 0),
+            Direction::Down => Vec2::new(0, 1),
+            Direction::Left => Vec2::new(-1, 0),
+        }
+    }
+
+    /// A quarter turn clockwise.
+    pub fn turn_right(self) -> Self {
+        Self::ALL[(self as usize + 1) % 4]
+    }
+
+    /// A quarter turn counter-clockwise.
+    pub fn turn_left(self) -> Self {
+        Self::ALL[(self as usize + 3) % 4]
+    }
+
+    pub fn opposite(self) -> Self {
+        Self::ALL[(self as usize + 2) % 4]
+    }
+}
+
+/// The eight directions to the points around one on a grid, diagonals included.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction8 {
+    Up,
+    UpRight,
+    Right,
+    DownRight,
+    Down,
+    DownLeft,
+    Left,
+    UpLeft,
+}
+
+impl Direction8 {
+    /// Clockwise, starting with `Up`.
+    pub const ALL: [Direction8; 8] = [
+        Direction8::Up,
+        Direction8::UpRight,
+        Direction8::Right,
+        Direction8::DownRight,
+        Direction8::Down,
+        Direction8::DownLeft,
+        Direction8::Left,
+        Direction8::UpLeft,
+    ];
+
+    pub fn offset(self) -> Vec2 {
+        match self {
+            Direction8::Up => Vec2::new(0, -1),
+            Direction8::UpRight => Vec2::new(1, -1),
+            Direction8::Right => Vec2::new(1, 0),
+            Direction8::DownRight => Vec2::new(1, 1),
+            Direction8::Down => Vec2::new(0, 1),
+            Direction8::DownLeft => Vec2::new(-1, 1),
+            Direction8::Left => Vec2::new(-1, 0),
+            Direction8::UpLeft => Vec2::new(-1, -1),
+        }
+    }
+
+    /// An eighth of a turn clockwise.
+    pub fn turn_right(self) -> Self {
+        Self::ALL[(self as usize + 1) % 8]
+    }
+
+    /// An eighth of a turn counter-clockwise.
+    pub fn turn_left(self) -> Self {
+        Self::ALL[(self as usize + 7) % 8]
+    }
+
+    pub fn opposite(self) -> Self {
+        Self::ALL[(self as usize + 4) % 8]
+    }
+}
+
+impl From<Direction> for Direction8 {
+    fn from(dir: Direction) -> Self {
+        Direction8::ALL[dir as usize * 2]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arithmetic() {
+        let mut v = Vec2::new(1, 2) + Vec2::new(3, -5);
+        assert_eq!(v, Vec2::new(4, -3));
+        assert_eq!(v - Vec2::new(4, 4), Vec2::new(0, -7));
+        assert_eq!(-v * 2, Vec2::new(-8, 6));
+
+        v += Vec2::new(1, 1);
+        v -= Vec2::new(0, 2);
+        assert_eq!(v, Vec2::new(5, -4));
+        assert_eq!(v.signum(), Vec2::new(1, -1));
+
+        assert_eq!(Vec3::new(1, 2, 3) + Vec3::new(1, 1, 1), Vec3::new(2, 3, 4));
+    }
+
+    #[test]
+    fn test_norms() {
+        let v = Vec2::new(3, -4);
+        assert_eq!(v.manhattan_norm(), 7);
+        assert_eq!(v.chebyshev_norm(), 4);
+        assert_eq!(v.euclidean_norm(), 5.0);
+
+        let (a, b) = (Vec3::new(1, 1, 1), Vec3::new(3, -2, 7));
+        assert_eq!(a.manhattan(&b), 11);
+        assert_eq!(a.chebyshev(&b), 6);
+        assert_eq!(a.euclidean(&b), 7.0);
+
+        assert_eq!(Vec2::new(0.5, -1.5).manhattan_norm(), 2.0);
+    }
+
+    #[test]
+    fn test_neighbourhoods() {
+        let p = Vec2::new(5, 5);
+        let neighbours: Vec<_> = p.neighbours4().collect();
+        assert_eq!(neighbours, [Vec2::new(5, 4), Vec2::new(6, 5), Vec2::new(5, 6), Vec2::new(4, 5)]);
+
+        assert_eq!(p.neighbours8().count(), 8);
+        assert!(p.neighbours8().all(|n| n.chebyshev(&p) == 1));
+    }
+
+    #[test]
+    fn test_rotations() {
+        use Direction::*;
+        assert_eq!(Up.turn_right(), Right);
+        assert_eq!(Up.turn_left(), Left);
+        assert_eq!(Left.turn_right(), Up);
+        assert_eq!(Right.opposite(), Left);
+        for dir in Direction::ALL {
+            assert_eq!(dir.turn_left().turn_right(), dir);
+            assert_eq!(dir.offset() + dir.opposite().offset(), Vec2::default());
+            assert_eq!(Direction8::from(dir).offset(), dir.offset());
+        }
+
+        assert_eq!(Direction8::UpLeft.turn_right(), Direction8::Up);
+        assert_eq!(Direction8::Up.turn_left(), Direction8::UpLeft);
+        assert_eq!(Direction8::DownRight.opposite(), Direction8::UpLeft);
+    }
+}
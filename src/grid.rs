@@ -0,0 +1,279 @@
+//! Maps of cells, like the ones the puzzles draw with characters.
+//!
+//! `Grid` stores a full rectangle and suits maps that are given in the input.
+//! `SparseGrid` only stores the cells that were set and suits maps that grow
+//! while solving, or that are mostly empty.
+
+use std::{
+    collections::HashMap,
+    ops::{Index, IndexMut},
+};
+
+use itertools::Itertools;
+use ndarray::Array2;
+
+use crate::error::ParseError;
+use crate::geometry::Vec2;
+
+/// What both kinds of grid can do. Positions are `Vec2`s with `x` the column
+/// and `y` the row.
+pub trait GridView<T> {
+    /// The cell at `pos`, or `None` if that's off the grid.
+    fn get(&self, pos: Vec2) -> Option<&T>;
+
+    /// The top left and bottom right corner of the smallest rectangle with
+    /// every cell in it, or `None` for an empty grid.
+    fn bounds(&self) -> Option<(Vec2, Vec2)>;
+
+    fn contains(&self, pos: Vec2) -> bool {
+        self.get(pos).is_some()
+    }
+
+    /// The points next to `pos` along the axes that are on the grid.
+    fn neighbours4(&self, pos: Vec2) -> impl Iterator<Item = Vec2> {
+        pos.neighbours4().filter(|n| self.contains(*n))
+    }
+
+    /// The points around `pos`, diagonals included, that are on the grid.
+    fn neighbours8(&self, pos: Vec2) -> impl Iterator<Item = Vec2> {
+        pos.neighbours8().filter(|n| self.contains(*n))
+    }
+
+    /// Draws the grid the way the puzzles do, a line per row.
+    fn render(&self, to_char: impl Fn(&T) -> char) -> String {
+        let Some((min, max)) = self.bounds() else {
+            return String::new();
+        };
+
+        (min.y..=max.y)
+            .map(|y| {
+                (min.x..=max.x)
+                    .map(|x| self.get(Vec2::new(x, y)).map_or(' ', &to_char))
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// A rectangular grid with a value in every cell.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    /// Row by row.
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Grid { width, height, cells: vec![fill; width * height] }
+    }
+
+    /// Reads a map with a cell per character. `cell` says what a character
+    /// stands for, or `None` if it doesn't belong on the map.
+    pub fn parse(input: &str, cell: impl Fn(char) -> Option<T>) -> Result<Self, ParseError<'_>> {
+        let mut cells = vec![];
+        let mut width = None;
+        let mut height = 0;
+
+        for line in input.lines() {
+            let row_start = cells.len();
+            for (pos, c) in line.char_indices() {
+                let value = cell(c).ok_or_else(|| ParseError::new(
+                    &line[pos..],
+                    format!("'{}' doesn't belong on this map", c),
+                ))?;
+                cells.push(value);
+            }
+
+            let row_width = cells.len() - row_start;
+            if *width.get_or_insert(row_width) != row_width {
+                return Err(ParseError::new(
+                    line,
+                    format!("Expected {} cells in every row", width.unwrap()),
+                ));
+            }
+            height += 1;
+        }
+
+        match width {
+            Some(width) if width > 0 => Ok(Grid { width, height, cells }),
+            _ => Err(ParseError::end_of_input("Expected a map")),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get_mut(&mut self, pos: Vec2) -> Option<&mut T> {
+        let i = self.index_of(pos)?;
+        Some(&mut self.cells[i])
+    }
+
+    /// Every position on the grid, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Vec2> {
+        let width = self.width;
+        (0..self.cells.len()).map(move |i| Vec2::new((i % width) as i32, (i / width) as i32))
+    }
+
+    /// Every cell with its position, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Vec2, &T)> {
+        self.positions().zip(&self.cells)
+    }
+
+    /// The first position, row by row, with a cell that matches.
+    pub fn find(&self, predicate: impl Fn(&T) -> bool) -> Option<Vec2> {
+        self.iter().find(|(_, cell)| predicate(cell)).map(|(pos, _)| pos)
+    }
+
+    /// A grid of the same size with `f` applied to every cell.
+    pub fn map<U>(&self, f: impl Fn(&T) -> U) -> Grid<U> {
+        Grid { width: self.width, height: self.height, cells: self.cells.iter().map(f).collect() }
+    }
+
+    fn index_of(&self, pos: Vec2) -> Option<usize> {
+        let (x, y) = (usize::try_from(pos.x).ok()?, usize::try_from(pos.y).ok()?);
+        (x < self.width && y < self.height).then_some(y * self.width + x)
+    }
+}
+
+impl<T> GridView<T> for Grid<T> {
+    fn get(&self, pos: Vec2) -> Option<&T> {
+        self.index_of(pos).map(|i| &self.cells[i])
+    }
+
+    fn bounds(&self) -> Option<(Vec2, Vec2)> {
+        let max = Vec2::new(self.width as i32 - 1, self.height as i32 - 1);
+        (!self.cells.is_empty()).then_some((Vec2::default(), max))
+    }
+}
+
+impl<T> Index<Vec2> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Vec2) -> &T {
+        self.get(pos).unwrap_or_else(|| panic!("{:?} is off the grid", pos))
+    }
+}
+
+impl<T> IndexMut<Vec2> for Grid<T> {
+    fn index_mut(&mut self, pos: Vec2) -> &mut T {
+        self.get_mut(pos).unwrap_or_else(|| panic!("{:?} is off the grid", pos))
+    }
+}
+
+/// For the number crunching that ndarray is better at. Rows become the first axis.
+impl<T> From<Grid<T>> for Array2<T> {
+    fn from(grid: Grid<T>) -> Self {
+        Array2::from_shape_vec((grid.height, grid.width), grid.cells)
+            .expect("The grid has a cell for every row and column")
+    }
+}
+
+/// A grid without edges that stores only the cells that were set. Every other
+/// cell has the default value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SparseGrid<T> {
+    cells: HashMap<Vec2, T>,
+    default: T,
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new(default: T) -> Self {
+        SparseGrid { cells: HashMap::new(), default }
+    }
+
+    pub fn set(&mut self, pos: Vec2, value: T) {
+        self.cells.insert(pos, value);
+    }
+
+    /// The cells that were set, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (Vec2, &T)> {
+        self.cells.iter().map(|(pos, cell)| (*pos, cell))
+    }
+}
+
+impl<T: Default> Default for SparseGrid<T> {
+    fn default() -> Self {
+        SparseGrid::new(T::default())
+    }
+}
+
+impl<T> GridView<T> for SparseGrid<T> {
+    fn get(&self, pos: Vec2) -> Option<&T> {
+        Some(self.cells.get(&pos).unwrap_or(&self.default))
+    }
+
+    fn bounds(&self) -> Option<(Vec2, Vec2)> {
+        let (min_x, max_x) = self.cells.keys().map(|pos| pos.x).minmax().into_option()?;
+        let (min_y, max_y) = self.cells.keys().map(|pos| pos.y).minmax().into_option()?;
+        Some((Vec2::new(min_x, min_y), Vec2::new(max_x, max_y)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    const MAP: &str = indoc! {"
+        S.#
+        ..E"};
+
+    #[test]
+    fn test_parse() {
+        let grid = Grid::parse(MAP, Some).unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[Vec2::new(2, 0)], '#');
+        assert_eq!(grid.find(|&c| c == 'E'), Some(Vec2::new(2, 1)));
+        assert_eq!(grid.get(Vec2::new(3, 0)), None);
+        assert_eq!(grid.get(Vec2::new(0, -1)), None);
+
+        let digits = |c: char| c.to_digit(10);
+        assert_eq!(Grid::parse("12\n3x", digits).unwrap_err().at, "x");
+        assert_eq!(Grid::parse("12\n345", digits).unwrap_err().at, "345");
+        assert!(Grid::parse("", digits).is_err());
+    }
+
+    #[test]
+    fn neighbours_stay_on_the_grid() {
+        let grid = Grid::new(3, 2, 0);
+        assert_eq!(grid.neighbours4(Vec2::new(0, 0)).collect_vec(), [Vec2::new(1, 0), Vec2::new(0, 1)]);
+        assert_eq!(grid.neighbours4(Vec2::new(1, 1)).count(), 3);
+        assert_eq!(grid.neighbours8(Vec2::new(1, 1)).count(), 5);
+    }
+
+    #[test]
+    fn maps_and_renders() {
+        let mut grid = Grid::parse(MAP, Some).unwrap().map(|&c| c == '#');
+        grid[Vec2::new(0, 1)] = true;
+        assert_eq!(grid.render(|&wall| if wall { '#' } else { '.' }), "..#\n#..");
+        assert_eq!(grid.positions().count(), 6);
+
+        let array: Array2<bool> = grid.into();
+        assert!(array[[1, 0]]);
+    }
+
+    #[test]
+    fn sparse_grids_grow_as_needed() {
+        let mut grid = SparseGrid::new('.');
+        assert_eq!(grid.bounds(), None);
+        assert_eq!(grid.render(|&c| c), "");
+
+        grid.set(Vec2::new(-1, 2), '#');
+        grid.set(Vec2::new(1, 3), 'o');
+        assert_eq!(grid.get(Vec2::new(100, 100)), Some(&'.'));
+        assert_eq!(grid.neighbours8(Vec2::new(100, 100)).count(), 8);
+        assert_eq!(grid.bounds(), Some((Vec2::new(-1, 2), Vec2::new(1, 3))));
+        assert_eq!(grid.render(|&c| c), "#..\n..o");
+    }
+}
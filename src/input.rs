@@ -0,0 +1,175 @@
+use std::{
+    error::Error,
+    fmt::Display,
+    fs,
+    io::{self, IsTerminal, Read},
+    path::PathBuf,
+};
+
+use aocf::Aoc;
+use simple_error::SimpleError;
+
+/// Where the puzzle input for a day came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputSource {
+    File(PathBuf),
+    Stdin,
+    Aocf,
+}
+
+impl Display for InputSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InputSource::File(path) => write!(f, "{}", path.display()),
+            InputSource::Stdin => write!(f, "stdin"),
+            InputSource::Aocf => write!(f, "aocf"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Input {
+    pub text: String,
+    pub source: InputSource,
+}
+
+/// Finds the puzzle input for a day. In order, we try
+/// 1. an explicitly given input file,
+/// 2. whatever got piped into stdin,
+/// 3. `dayNN.txt` in the inputs directory,
+/// 4. aocf, which either has the input cached or downloads it with the session cookie.
+///
+/// The first two only make sense when running a single day.
+pub struct InputResolver {
+    pub year: i32,
+    pub input_file: Option<PathBuf>,
+    pub stdin: Option<String>,
+    pub inputs_dir: PathBuf,
+    pub use_aocf: bool,
+    pub session_cookie: Option<String>,
+}
+
+impl InputResolver {
+    pub fn resolve(&self, day: u32) -> Result<Input, Box<dyn Error>> {
+        if let Some(path) = &self.input_file {
+            let text = fs::read_to_string(path)
+                .map_err(|e| SimpleError::new(format!("Couldn't read {}: {}", path.display(), e)))?;
+            return Ok(Input { text, source: InputSource::File(path.clone()) });
+        }
+
+        if let Some(text) = &self.stdin {
+            return Ok(Input { text: text.clone(), source: InputSource::Stdin });
+        }
+
+        let mut failures = vec![];
+
+        let path = self.inputs_dir.join(format!("day{:02}.txt", day));
+        match fs::read_to_string(&path) {
+            Ok(text) => return Ok(Input { text, source: InputSource::File(path) }),
+            Err(e) => failures.push(format!("{}: {}", path.display(), e)),
+        }
+
+        if self.use_aocf {
+            match self.get_aoc_input(day) {
+                Ok(text) => return Ok(Input { text, source: InputSource::Aocf }),
+                Err(e) => failures.push(format!("aocf: {}", e)),
+            }
+        }
+
+        Err(Box::new(SimpleError::new(format!(
+            "No input found for day {} ({})",
+            day,
+            failures.join("; ")
+        ))))
+    }
+
+    fn get_aoc_input(&self, day: u32) -> Result<String, Box<dyn Error>> {
+        let mut aoc = Aoc::new().year(Some(self.year)).day(Some(day));
+        if let Some(session_str) = &self.session_cookie {
+            aoc = aoc.cookie(session_str);
+        }
+
+        aoc = aoc
+            .init()
+            .map_err(|e| SimpleError::new(format!("couldn't find or create its cache ({})", e)))?;
+        let input = aoc
+            .get_input(false)
+            .map_err(|e| SimpleError::new(format!("couldn't get the input ({})", e)))?;
+        Ok(input)
+    }
+}
+
+/// Reads stdin if something got piped into it. Returns `None` for an interactive
+/// terminal or an empty pipe, so that those fall through to the other sources.
+pub fn read_piped_stdin() -> io::Result<Option<String>> {
+    let mut stdin = io::stdin();
+    if stdin.is_terminal() {
+        return Ok(None);
+    }
+
+    let mut text = String::new();
+    stdin.read_to_string(&mut text)?;
+    if text.trim().is_empty() {
+        Ok(None)
+    } else {
+        Ok(Some(text))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_inputs_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("aoc_2022_{}_{}", name, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("day03.txt"), "from the inputs dir").unwrap();
+        dir
+    }
+
+    fn offline_resolver(inputs_dir: PathBuf) -> InputResolver {
+        InputResolver {
+            year: 2022,
+            input_file: None,
+            stdin: None,
+            inputs_dir,
+            use_aocf: false,
+            session_cookie: None,
+        }
+    }
+
+    #[test]
+    fn reads_from_inputs_dir() {
+        let dir = make_inputs_dir("inputs_dir");
+        let resolver = offline_resolver(dir.clone());
+
+        let input = resolver.resolve(3).unwrap();
+        assert_eq!(input.text, "from the inputs dir");
+        assert_eq!(input.source, InputSource::File(dir.join("day03.txt")));
+
+        let error = resolver.resolve(4).unwrap_err().to_string();
+        assert!(error.contains("day 4"), "{}", error);
+        assert!(error.contains("day04.txt"), "{}", error);
+    }
+
+    #[test]
+    fn explicit_sources_take_precedence() {
+        let dir = make_inputs_dir("precedence");
+        let mut resolver = offline_resolver(dir.clone());
+
+        resolver.stdin = Some("from stdin".to_string());
+        let input = resolver.resolve(3).unwrap();
+        assert_eq!(input.source, InputSource::Stdin);
+        assert_eq!(input.text, "from stdin");
+
+        let file = dir.join("my_input.txt");
+        fs::write(&file, "from a file").unwrap();
+        resolver.input_file = Some(file.clone());
+        let input = resolver.resolve(3).unwrap();
+        assert_eq!(input.source, InputSource::File(file));
+        assert_eq!(input.text, "from a file");
+
+        resolver.input_file = Some(dir.join("does_not_exist.txt"));
+        assert!(resolver.resolve(3).is_err());
+    }
+}
@@ -0,0 +1,296 @@
+//! Closed ranges of integers, and sets of them, for the puzzles about sections
+//! of a line: the cleanup assignments of day 4 and the rows of day 15.
+
+use std::collections::BTreeMap;
+
+use itertools::Itertools;
+
+/// The integers `low..=high`. An interval is never empty.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Interval {
+    low: i64,
+    high: i64,
+}
+
+impl Interval {
+    /// The integers from `low` to `high`, both included. `low` can't be more
+    /// than `high`; parsers check that before they get here.
+    pub fn new(low: i64, high: i64) -> Self {
+        debug_assert!(low <= high, "An interval can't go from {} down to {}", low, high);
+        Interval { low, high }
+    }
+
+    pub fn low(&self) -> i64 {
+        self.low
+    }
+
+    pub fn high(&self) -> i64 {
+        self.high
+    }
+
+    /// How many integers it has. That's never 0.
+    pub fn size(&self) -> usize {
+        (self.high - self.low + 1) as usize
+    }
+
+    pub fn contains(&self, x: i64) -> bool {
+        (self.low..=self.high).contains(&x)
+    }
+
+    /// Whether all of `other` is in this interval.
+    pub fn contains_interval(&self, other: &Interval) -> bool {
+        other.low >= self.low && other.high <= self.high
+    }
+
+    pub fn overlaps(&self, other: &Interval) -> bool {
+        other.low <= self.high && other.high >= self.low
+    }
+
+    /// The integers in both intervals, if there are any.
+    pub fn intersection(&self, other: &Interval) -> Option<Interval> {
+        let (low, high) = (self.low.max(other.low), self.high.min(other.high));
+        (low <= high).then_some(Interval { low, high })
+    }
+}
+
+/// A set of integers, kept as the fewest intervals that cover it.
+///
+/// The intervals are stored by their lower end, and never overlap or touch:
+/// inserting `3..=5` into `{0..=2, 6..=9}` leaves `{0..=9}`. Inserting or
+/// removing an interval takes `O(log n)`, plus a bit for every interval it
+/// merges with or cuts up.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IntervalSet {
+    /// The upper end of each interval, by its lower end.
+    intervals: BTreeMap<i64, i64>,
+}
+
+impl IntervalSet {
+    pub fn new() -> Self {
+        IntervalSet::default()
+    }
+
+    /// How many integers are in the set.
+    pub fn len(&self) -> usize {
+        self.iter().map(|interval| interval.size()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// The intervals that make up the set, from lowest to highest.
+    pub fn iter(&self) -> impl Iterator<Item = Interval> + '_ {
+        self.intervals.iter().map(|(&low, &high)| Interval { low, high })
+    }
+
+    /// The interval that contains `x`, if any.
+    fn interval_at(&self, x: i64) -> Option<Interval> {
+        let (&low, &high) = self.intervals.range(..=x).next_back()?;
+        (high >= x).then_some(Interval { low, high })
+    }
+
+    pub fn contains(&self, x: i64) -> bool {
+        self.interval_at(x).is_some()
+    }
+
+    pub fn insert(&mut self, interval: Interval) {
+        let Interval { mut low, mut high } = interval;
+
+        // an interval that starts before this one and reaches it swallows it,
+        // and then this one swallows everything that starts inside it
+        if let Some(before) = self.interval_at(low.saturating_sub(1)) {
+            low = before.low;
+        }
+        let swallowed = self.intervals.range(low..=high.saturating_add(1)).map(|(&low, _)| low).collect_vec();
+        for start in swallowed {
+            high = high.max(self.intervals.remove(&start).unwrap());
+        }
+
+        self.intervals.insert(low, high);
+    }
+
+    pub fn remove(&mut self, interval: Interval) {
+        let before = self.interval_at(interval.low).filter(|before| before.low < interval.low);
+        let inside = self.intervals.range(interval.low..=interval.high).map(|(&low, &high)| Interval { low, high });
+        let overlapping = before.into_iter().chain(inside).collect_vec();
+
+        // only the ends of the first and the last one can stick out
+        for cut in overlapping {
+            self.intervals.remove(&cut.low);
+            if cut.low < interval.low {
+                self.intervals.insert(cut.low, interval.low - 1);
+            }
+            if cut.high > interval.high {
+                self.intervals.insert(interval.high + 1, cut.high);
+            }
+        }
+    }
+
+    /// The integers in both sets.
+    pub fn intersection(&self, other: &IntervalSet) -> IntervalSet {
+        let (mut ours, mut theirs) = (self.iter().peekable(), other.iter().peekable());
+        let mut both = IntervalSet::new();
+        while let (Some(a), Some(b)) = (ours.peek(), theirs.peek()) {
+            if let Some(common) = a.intersection(b) {
+                both.insert(common);
+            }
+            // whichever ends first can't overlap anything else
+            if a.high < b.high {
+                ours.next();
+            } else {
+                theirs.next();
+            }
+        }
+        both
+    }
+
+    /// The integers within `bounds` that aren't in the set.
+    pub fn complement(&self, bounds: Interval) -> IntervalSet {
+        let mut complement = IntervalSet::from_iter([bounds]);
+        for interval in self.iter().filter(|interval| interval.overlaps(&bounds)) {
+            complement.remove(interval);
+        }
+        complement
+    }
+
+    /// The holes between the intervals of the set, from lowest to highest.
+    pub fn gaps(&self) -> impl Iterator<Item = Interval> + '_ {
+        self.iter().tuple_windows().map(|(a, b)| Interval { low: a.high + 1, high: b.low - 1 })
+    }
+}
+
+impl FromIterator<Interval> for IntervalSet {
+    fn from_iter<I: IntoIterator<Item = Interval>>(intervals: I) -> Self {
+        let mut set = IntervalSet::new();
+        for interval in intervals {
+            set.insert(interval);
+        }
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::*;
+    use crate::random::Random;
+
+    #[test]
+    fn test_interval() {
+        assert_eq!(Interval::new(2, 5).size(), 4);
+        assert!(Interval::new(2, 5).contains_interval(&Interval::new(3, 5)));
+        assert!(!Interval::new(3, 5).contains_interval(&Interval::new(2, 5)));
+        assert!(Interval::new(2, 4).overlaps(&Interval::new(4, 10)));
+        assert!(!Interval::new(2, 4).overlaps(&Interval::new(5, 6)));
+        assert_eq!(Interval::new(2, 4).intersection(&Interval::new(3, 9)), Some(Interval::new(3, 4)));
+        assert_eq!(Interval::new(2, 4).intersection(&Interval::new(5, 9)), None);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "An interval can't go from 5 down to 2")]
+    fn intervals_go_up() {
+        Interval::new(5, 2);
+    }
+
+    #[test]
+    fn test_interval_set_computations() {
+        let interval = Interval::new(0, 9);
+        let mut intervals = IntervalSet::new();
+
+        intervals.insert(interval);
+        assert_eq!(intervals.len(), interval.size(), "Single interval should just get added to interval set");
+
+        intervals.insert(Interval::new(13, 14));
+        assert_eq!(intervals.len(), 12, "Adding disjoint interval increases len by that amount");
+
+        intervals.insert(Interval::new(3, 7));
+        assert_eq!(intervals.len(), 12, "Adding subsumed interval doesn't change length");
+
+        intervals.insert(Interval::new(-5, 3));
+        assert_eq!(intervals.len(), 17);
+    }
+
+    #[test]
+    fn intervals_merge_and_split() {
+        let mut set = IntervalSet::from_iter([Interval::new(0, 2), Interval::new(6, 9), Interval::new(12, 12)]);
+        assert_eq!(set.len(), 8);
+        assert_eq!(set.gaps().collect_vec(), [Interval::new(3, 5), Interval::new(10, 11)]);
+
+        set.insert(Interval::new(3, 5));
+        assert_eq!(set.iter().collect_vec(), [Interval::new(0, 9), Interval::new(12, 12)]);
+
+        set.remove(Interval::new(4, 12));
+        assert_eq!(set.iter().collect_vec(), [Interval::new(0, 3)]);
+        assert!(set.contains(3) && !set.contains(4));
+
+        let bounds = Interval::new(-2, 6);
+        assert_eq!(set.complement(bounds).iter().collect_vec(), [Interval::new(-2, -1), Interval::new(4, 6)]);
+    }
+
+    /// A random interval between -20 and 20.
+    fn random_interval(random: &mut Random) -> Interval {
+        let (a, b) = (random.below(41) as i64 - 20, random.below(41) as i64 - 20);
+        Interval::new(a.min(b), a.max(b))
+    }
+
+    fn points(interval: Interval) -> BTreeSet<i64> {
+        (interval.low..=interval.high).collect()
+    }
+
+    /// The set again, one integer at a time.
+    fn all_points(set: &IntervalSet) -> BTreeSet<i64> {
+        set.iter().flat_map(points).collect()
+    }
+
+    /// The intervals are in order, and there's a gap between each two.
+    fn check_invariants(set: &IntervalSet) {
+        for (a, b) in set.iter().tuple_windows() {
+            assert!(a.high + 1 < b.low, "{:?} and {:?} should have been merged", a, b);
+        }
+    }
+
+    #[test]
+    fn sets_agree_with_sets_of_points() {
+        let mut random = Random::new(42);
+        for _ in 0..200 {
+            let mut set = IntervalSet::new();
+            let mut expected = BTreeSet::new();
+            for _ in 0..10 {
+                let interval = random_interval(&mut random);
+                if random.below(3) == 0 {
+                    set.remove(interval);
+                    expected.retain(|x| !interval.contains(*x));
+                } else {
+                    set.insert(interval);
+                    expected.extend(points(interval));
+                }
+                check_invariants(&set);
+                assert_eq!(all_points(&set), expected);
+            }
+
+            assert_eq!(set.len(), expected.len());
+            assert_eq!(set.is_empty(), expected.is_empty());
+            assert!((-25..=25).all(|x| set.contains(x) == expected.contains(&x)));
+
+            let other = IntervalSet::from_iter((0..3).map(|_| random_interval(&mut random)));
+            let intersection = set.intersection(&other);
+            check_invariants(&intersection);
+            assert_eq!(all_points(&intersection), &expected & &all_points(&other));
+
+            let bounds = random_interval(&mut random);
+            let complement = set.complement(bounds);
+            check_invariants(&complement);
+            assert_eq!(all_points(&complement), &points(bounds) - &expected);
+
+            let gaps = set.gaps().flat_map(points).collect::<BTreeSet<_>>();
+            let hull = match (expected.first(), expected.last()) {
+                (Some(&low), Some(&high)) => points(Interval::new(low, high)),
+                _ => BTreeSet::new(),
+            };
+            assert_eq!(gaps, &hull - &expected);
+        }
+    }
+}
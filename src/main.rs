@@ -17,134 +17,286 @@ pub mod day15;
 pub mod day16;
 pub mod day20;
 
-use aocf::Aoc;
-use day01::run_day_01;
-use day02::run_day_02;
-use day03::run_day_03;
-use day04::run_day_04;
-use day05::run_day_05;
-use day06::run_day_06;
-use day07::run_day_07;
-use day08::run_day_08;
-use day09::run_day_09;
-use day10::run_day_10;
-use day11::run_day_11;
-use day12::run_day_12;
-use day13::run_day_13;
-use day14::run_day_14;
-use day15::run_day_15;
-use day16::run_day_16;
-use day20::run_day_20;
+pub mod answers;
+pub mod cli;
+pub mod error;
+pub mod geometry;
+pub mod grid;
+pub mod input;
+pub mod interval;
+pub mod parallel;
+pub mod picture;
+pub mod report;
+pub mod search;
+pub mod solver;
+pub mod timing;
+
+#[cfg(test)]
+mod regression;
+#[cfg(test)]
+mod random;
+
+use answers::{AnswerSheet, Verdict};
+use clap::Parser;
+use itertools::Itertools;
+use cli::{Cli, Format};
+use input::{read_piped_stdin, Input, InputResolver};
+use parallel::for_each_in_order;
+use picture::Picture;
+use report::{to_csv, to_json, DayReport, Outcome};
+use solver::{find_solver, Answer, DrawOptions, Solver, SOLVERS};
+use timing::{summary_table, DayTimings};
+use toml::Table;
+
+use std::collections::HashMap;
 
 use std::env;
-use std::error::Error;
+use std::io::{self, Write};
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+use std::process::exit;
 
 fn main() {
-    let session_cookie: Option<String> = env::var("aocd_session_id").ok();
+    let cli = Cli::parse();
 
-    let year = 2022;
+    let available: Vec<u32> = SOLVERS.iter().map(|solver| solver.day()).collect();
+    let days = cli.selected_days(&available);
 
-    if let Ok(input) = get_aoc_input(year, 1, &session_cookie) {
-        run_day_01(input);
-        print_separation();
+    if cli.input.is_some() && days.len() != 1 {
+        eprintln!("--input only works when running a single day.");
+        exit(2);
     }
-
-    if let Ok(input) = get_aoc_input(year, 2, &session_cookie) {
-        run_day_02(input);
-        print_separation()
+    if matches!(cli.draw, Some(Some(_))) && days.len() != 1 {
+        eprintln!("--draw with a file only works when running a single day.");
+        exit(2);
     }
-
-    if let Ok(input) = get_aoc_input(year, 3, &session_cookie) {
-        run_day_03(input);
-        print_separation()
+    if cli.watch.is_some() && days.len() != 1 {
+        eprintln!("--watch only works when running a single day.");
+        exit(2);
     }
-
-    if let Ok(input) = get_aoc_input(year, 4, &session_cookie) {
-        run_day_04(input);
-        print_separation();
+    if cli.watch.is_some() && cli.format != Format::Text {
+        // the frames would end up between the records on stdout
+        eprintln!("--watch only works with the text format.");
+        exit(2);
     }
-
-    if let Ok(input) = get_aoc_input(year, 5, &session_cookie) {
-        run_day_05(input);
-        print_separation();
+    if cli.floor && cli.draw.is_none() && cli.watch.is_none() {
+        eprintln!("--floor only changes what --draw and --watch show.");
+        exit(2);
     }
 
-    if let Ok(input) = get_aoc_input(year, 6, &session_cookie) {
-        run_day_06(input);
-        print_separation();
-    }
+    // stdin can only feed a single day, and we can only read it once
+    let stdin = if days.len() == 1 && cli.input.is_none() {
+        read_piped_stdin().unwrap_or_else(|e| {
+            eprintln!("Couldn't read stdin: {}", e);
+            None
+        })
+    } else {
+        None
+    };
 
-    if let Ok(input) = get_aoc_input(year, 7, &session_cookie) {
-        run_day_07(input);
-        print_separation();
-    }
+    let mut sheet = AnswerSheet::load(&cli.answers).unwrap_or_else(|e| {
+        eprintln!("Couldn't read {}: {}", cli.answers.display(), e);
+        exit(2);
+    });
+    // we record answers in the sheet as we go, so take the settings out first
+    let settings: HashMap<u32, Table> = days
+        .iter()
+        .filter_map(|&day| Some((day, sheet.settings(day)?.clone())))
+        .collect();
+    let mut recorded_new_answers = false;
+    let mut timings = vec![];
+    let mut failed_days = vec![];
 
-    if let Ok(input) = get_aoc_input(year, 8, &session_cookie) {
-        run_day_08(input);
-        print_separation();
+    let resolver = InputResolver {
+        year: 2022,
+        input_file: cli.input.clone(),
+        stdin,
+        inputs_dir: cli.inputs_dir.clone(),
+        use_aocf: true,
+        session_cookie: env::var("aocd_session_id").ok(),
+    };
+
+    if let Some(frame_time) = cli.frame_time() {
+        watch(days[0], settings.get(&days[0]), &resolver, &cli.draw_options(), frame_time);
     }
 
-    if let Ok(input) = get_aoc_input(year, 9, &session_cookie) {
-        run_day_09(input);
-        print_separation();
+    let parts = cli.selected_parts();
+    let mut records = vec![];
+    let solve = |&day: &u32| {
+        let draw = cli.draw.is_some().then(|| cli.draw_options());
+        run_day(day, settings.get(&day), &resolver, &parts, cli.bench.unwrap_or(1), draw)
+    };
+    for_each_in_order(&days, cli.threads(), solve, |report| {
+        let day = report.day;
+        match cli.format {
+            Format::Text => print_report(&report, &sheet),
+            Format::Json | Format::Csv => records.extend(report.records(&sheet)),
+        }
+        if let (Some(file), Outcome::Solved { picture, .. }) = (&cli.draw, &report.outcome) {
+            show_picture(day, picture.as_ref(), file.as_deref(), cli.format);
+        }
+
+        match &report.outcome {
+            Outcome::Solved { runs, .. } => {
+                for part_run in &runs[0].parts {
+                    let (part, answer) = (part_run.part, &part_run.answer);
+                    if cli.record && sheet.check(day, part, answer) == Verdict::Unknown {
+                        sheet.record(day, part, answer);
+                        recorded_new_answers = true;
+                    }
+                }
+                timings.push(DayTimings::from_runs(day, report.title.unwrap_or_default(), runs));
+            }
+            Outcome::Failed { .. } => failed_days.push(day),
+            Outcome::Unsolved | Outcome::NoInput(_) => {}
+        }
+    });
+
+    match cli.format {
+        Format::Text if !timings.is_empty() => {
+            match cli.bench {
+                Some(runs) => println!("Timings over {} runs (mean ± stddev):", runs),
+                None => println!("Timings:"),
+            }
+            println!("{}", summary_table(&timings));
+        }
+        Format::Text => {}
+        Format::Json => println!("{}", to_json(&records)),
+        Format::Csv => println!("{}", to_csv(&records)),
     }
 
-    if let Ok(input) = get_aoc_input(year, 10, &session_cookie) {
-        run_day_10(input);
-        print_separation();
+    if recorded_new_answers {
+        // stdout is reserved for the records in the machine-readable formats
+        match sheet.save(&cli.answers) {
+            Ok(()) if cli.format == Format::Text => {
+                println!("Recorded new answers in {}", cli.answers.display())
+            }
+            Ok(()) => eprintln!("Recorded new answers in {}", cli.answers.display()),
+            Err(e) => eprintln!("Couldn't save answers to {}: {}", cli.answers.display(), e),
+        }
     }
 
-    if let Ok(input) = get_aoc_input(year, 11, &session_cookie) {
-        run_day_11(input);
-        print_separation();
+    if !failed_days.is_empty() {
+        eprintln!("Failed to solve day(s) {}", failed_days.iter().join(", "));
+        exit(1);
     }
+}
 
-    // if let Ok(input) = get_aoc_input(year, 12, &session_cookie) {
-    //     run_day_12(input);
-    //     print_separation();
-    // } // commenting out because it's slow unless compiled with release
+/// Solves the given `parts` of a day `rounds` times, on the same input and
+/// with the `settings` from the answer sheet, and draws it if there are `draw`
+/// options.
+fn run_day(
+    day: u32,
+    settings: Option<&Table>,
+    resolver: &InputResolver,
+    parts: &[u8],
+    rounds: u32,
+    draw: Option<DrawOptions>,
+) -> DayReport {
+    let Some(solver) = find_solver(day) else {
+        return DayReport { day, title: None, parts: parts.to_vec(), outcome: Outcome::Unsolved };
+    };
 
-    if let Ok(input) = get_aoc_input(year, 13, &session_cookie) {
-        run_day_13(input);
-        print_separation();
+    let outcome = match resolver.resolve(day) {
+        Ok(input) => match settings.map(|settings| solver.with_settings(settings)) {
+            None => solve(solver, input, parts, rounds, draw),
+            Some(Ok(configured)) => solve(configured.as_ref(), input, parts, rounds, draw),
+            Some(Err(error)) => Outcome::Failed { source: input.source, error },
+        },
+        Err(e) => Outcome::NoInput(e.to_string()),
+    };
+
+    DayReport { day, title: Some(solver.title()), parts: parts.to_vec(), outcome }
+}
+
+fn solve(solver: &dyn Solver, input: Input, parts: &[u8], rounds: u32, draw: Option<DrawOptions>) -> Outcome {
+    match (0..rounds).map(|_| solver.run(&input.text, parts)).collect() {
+        Ok(runs) => {
+            // the input parsed fine for the runs, so it does for drawing
+            let picture = draw.and_then(|options| solver.draw(&input.text, &options).ok().flatten());
+            Outcome::Solved { source: input.source, runs, picture }
+        }
+        Err(error) => Outcome::Failed { source: input.source, error },
     }
+}
 
-    if let Ok(input) = get_aoc_input(year, 14, &session_cookie) {
-        run_day_14(input);
-        print_separation();
+fn print_report(report: &DayReport, sheet: &AnswerSheet) {
+    let (day, title) = (report.day, report.title.unwrap_or_default());
+    match &report.outcome {
+        Outcome::Unsolved => println!("Day {} isn't solved yet.", day),
+        Outcome::NoInput(reason) => println!("Skipping day {}: {}", day, reason),
+        Outcome::Failed { source, error } => {
+            println!("Day {}: {} (input from {})", day, title, source);
+            println!("{}", error);
+        }
+        Outcome::Solved { source, runs, .. } => {
+            println!("Day {}: {} (input from {})", day, title, source);
+            for part_run in &runs[0].parts {
+                let (part, answer) = (part_run.part, &part_run.answer);
+                print_answer(part, answer, &sheet.check(day, part, answer));
+            }
+        }
     }
+    print_separation();
+}
 
-    // if let Ok(input) = get_aoc_input(year, 15, &session_cookie) {
-    //     run_day_15(input);
-    //     print_separation();
-    // }
+/// Pixels per character of a picture saved to a file.
+const PICTURE_CELL_SIZE: usize = 4;
 
-    // if let Ok(input) = get_aoc_input(year, 16, &session_cookie) {
-    //     run_day_16(input);
-    //     print_separation();
-    // }
+fn show_picture(day: u32, picture: Option<&Picture>, file: Option<&Path>, format: Format) {
+    let Some(picture) = picture else {
+        eprintln!("Day {} has nothing to draw.", day);
+        return;
+    };
 
-    if let Ok(input) = get_aoc_input(year, 20, &session_cookie) {
-        run_day_20(input);
-        print_separation();
+    match file {
+        // stdout is reserved for the records in the machine-readable formats
+        None if format == Format::Text => println!("{}", picture.to_ansi()),
+        None => eprintln!("{}", picture.to_ansi()),
+        Some(file) => match picture.to_image(PICTURE_CELL_SIZE).save(file) {
+            Ok(()) => eprintln!("Saved a picture of day {} to {}", day, file.display()),
+            Err(e) => eprintln!("Couldn't save the picture to {}: {}", file.display(), e),
+        },
     }
 }
 
-fn print_separation() {
-    print!("\n\n\n");
+/// Plays a day's animation in the terminal, before solving it as usual.
+fn watch(day: u32, settings: Option<&Table>, resolver: &InputResolver, options: &DrawOptions, frame_time: Duration) {
+    let Some(solver) = find_solver(day) else {
+        return;
+    };
+    // solving the day says what went wrong, if anything
+    let Ok(input) = resolver.resolve(day) else {
+        return;
+    };
+    let configured = match settings.map(|settings| solver.with_settings(settings)) {
+        Some(Ok(configured)) => Some(configured),
+        Some(Err(_)) => return,
+        None => None,
+    };
+    let solver = configured.as_deref().unwrap_or(solver);
+
+    let mut frame = |picture: &Picture| {
+        // back to the top left corner, and clear the screen
+        print!("\x1b[H\x1b[2J{}", picture.to_ansi());
+        io::stdout().flush().ok();
+        thread::sleep(frame_time);
+    };
+    match solver.animate(&input.text, options, &mut frame) {
+        Ok(true) => println!(),
+        Ok(false) => eprintln!("Day {} has no animation.", day),
+        Err(_) => {}
+    }
 }
 
-fn get_aoc_input(
-    year: i32,
-    day: u32,
-    session_cookie: &Option<String>,
-) -> Result<String, Box<dyn Error>> {
-    let mut aoc = Aoc::new().year(Some(year)).day(Some(day));
-    if let Some(session_str) = session_cookie {
-        aoc = aoc.cookie(session_str);
+fn print_answer(part: u8, answer: &Answer, verdict: &Verdict) {
+    match answer {
+        Answer::Crt(screen) => println!("Part {}: {}\n{}", part, verdict, screen),
+        _ => println!("Part {}: {} {}", part, answer, verdict),
     }
+}
 
-    aoc = aoc.init().unwrap();
-    let input = aoc.get_input(false)?;
-    Ok(input)
+fn print_separation() {
+    print!("\n\n\n");
 }
@@ -0,0 +1,89 @@
+use std::{
+    collections::BTreeMap,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc,
+    },
+    thread,
+};
+
+/// Runs `work` on every item on up to `threads` threads at once, and hands the
+/// results to `handle` in the order of the items, each as soon as it and all
+/// the ones before it are done.
+pub fn for_each_in_order<I: Sync, T: Send>(
+    items: &[I],
+    threads: usize,
+    work: impl Fn(&I) -> T + Sync,
+    mut handle: impl FnMut(T),
+) {
+    if threads <= 1 {
+        items.iter().for_each(|item| handle(work(item)));
+        return;
+    }
+
+    let next_item = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+
+    thread::scope(|scope| {
+        for _ in 0..threads.min(items.len()) {
+            let sender = sender.clone();
+            let (next_item, work) = (&next_item, &work);
+            scope.spawn(move || loop {
+                let i = next_item.fetch_add(1, Ordering::Relaxed);
+                let Some(item) = items.get(i) else { break };
+                if sender.send((i, work(item))).is_err() {
+                    break;
+                }
+            });
+        }
+        // the receiver stops once the workers are done with their senders
+        drop(sender);
+
+        let mut done = BTreeMap::new();
+        let mut next_to_handle = 0;
+        for (i, result) in receiver {
+            done.insert(i, result);
+            while let Some(result) = done.remove(&next_to_handle) {
+                handle(result);
+                next_to_handle += 1;
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn results_come_in_order() {
+        // the first items take longest, so they finish last
+        let items: Vec<u64> = (0..8).rev().collect();
+        let mut handled = vec![];
+
+        for_each_in_order(
+            &items,
+            4,
+            |&x| {
+                thread::sleep(Duration::from_millis(5 * x));
+                x * 10
+            },
+            |x| handled.push(x),
+        );
+
+        assert_eq!(handled, vec![70, 60, 50, 40, 30, 20, 10, 0]);
+    }
+
+    #[test]
+    fn items_run_at_the_same_time() {
+        let items = [(); 4];
+        let start = Instant::now();
+        let mut count = 0;
+
+        for_each_in_order(&items, 4, |_| thread::sleep(Duration::from_millis(100)), |_| count += 1);
+
+        assert_eq!(count, 4);
+        assert!(start.elapsed() < Duration::from_millis(300), "{:?}", start.elapsed());
+    }
+}
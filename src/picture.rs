@@ -0,0 +1,198 @@
+//! Pictures that days can draw of their puzzle, for the terminal or for an image file.
+
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use flate2::{write::ZlibEncoder, Compression};
+
+use crate::geometry::Vec2;
+use crate::grid::{Grid, GridView};
+
+pub type Rgb = [u8; 3];
+
+/// One character of a picture.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cell {
+    pub symbol: char,
+    pub colour: Rgb,
+}
+
+/// A map drawn with coloured characters.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Picture {
+    pub cells: Grid<Cell>,
+}
+
+impl Picture {
+    /// Just the characters, the way the puzzle texts draw their maps.
+    pub fn to_text(&self) -> String {
+        self.cells.render(|cell| cell.symbol)
+    }
+
+    /// The characters in colour, for terminals that understand 24-bit ANSI colour codes.
+    pub fn to_ansi(&self) -> String {
+        let mut out = String::new();
+        for y in 0..self.cells.height() {
+            let mut colour = None;
+            for x in 0..self.cells.width() {
+                let cell = self.cells[Vec2::new(x as i32, y as i32)];
+                if colour != Some(cell.colour) {
+                    let [r, g, b] = cell.colour;
+                    out += &format!("\x1b[38;2;{};{};{}m", r, g, b);
+                    colour = Some(cell.colour);
+                }
+                out.push(cell.symbol);
+            }
+            out += "\x1b[0m\n";
+        }
+        out
+    }
+
+    /// Every cell as a square of `cell_size` pixels in its colour. The
+    /// characters get lost, so colour is all that's left to tell cells apart.
+    pub fn to_image(&self, cell_size: usize) -> Image {
+        let (width, height) = (self.cells.width() * cell_size, self.cells.height() * cell_size);
+        let pixels = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x / cell_size, y / cell_size)))
+            .map(|(x, y)| self.cells[Vec2::new(x as i32, y as i32)].colour)
+            .collect();
+
+        Image { width, height, pixels }
+    }
+}
+
+/// Interpolates along a colour scale. `stops` are the colours at evenly spaced
+/// points from 0 to 1.
+pub fn gradient(stops: &[Rgb], t: f64) -> Rgb {
+    let t = t.clamp(0.0, 1.0) * (stops.len() - 1) as f64;
+    let i = (t.floor() as usize).min(stops.len() - 2);
+    let frac = t - i as f64;
+    let (from, to) = (stops[i], stops[i + 1]);
+    [0, 1, 2].map(|c| (from[c] as f64 + (to[c] as f64 - from[c] as f64) * frac).round() as u8)
+}
+
+/// Plain RGB pixels, row by row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<Rgb>,
+}
+
+impl Image {
+    /// Saves the image as PNG, or as PPM if the file name ends in `.ppm`.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let mut out = BufWriter::new(File::create(path)?);
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("ppm") => self.write_ppm(&mut out)?,
+            _ => self.write_png(&mut out)?,
+        }
+        out.flush()
+    }
+
+    /// The binary flavour of the simplest image format there is.
+    pub fn write_ppm(&self, out: &mut impl Write) -> io::Result<()> {
+        write!(out, "P6\n{} {}\n255\n", self.width, self.height)?;
+        out.write_all(&self.pixels.concat())
+    }
+
+    /// An uncomplicated PNG: 8-bit RGB, no interlacing, no row filters.
+    pub fn write_png(&self, out: &mut impl Write) -> io::Result<()> {
+        fn chunk(out: &mut impl Write, kind: &[u8; 4], data: &[u8]) -> io::Result<()> {
+            let mut crc = crc32fast::Hasher::new();
+            crc.update(kind);
+            crc.update(data);
+
+            out.write_all(&(data.len() as u32).to_be_bytes())?;
+            out.write_all(kind)?;
+            out.write_all(data)?;
+            out.write_all(&crc.finalize().to_be_bytes())
+        }
+
+        let mut header = vec![];
+        header.extend((self.width as u32).to_be_bytes());
+        header.extend((self.height as u32).to_be_bytes());
+        header.extend([8, 2, 0, 0, 0]); // bit depth, colour type RGB, compression, filter, interlace
+
+        let mut data = ZlibEncoder::new(vec![], Compression::default());
+        for row in self.pixels.chunks(self.width.max(1)) {
+            data.write_all(&[0])?; // no filter
+            data.write_all(&row.concat())?;
+        }
+
+        out.write_all(b"\x89PNG\r\n\x1a\n")?;
+        chunk(out, b"IHDR", &header)?;
+        chunk(out, b"IDAT", &data.finish()?)?;
+        chunk(out, b"IEND", &[])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    const RED: Rgb = [255, 0, 0];
+    const BLUE: Rgb = [0, 0, 255];
+
+    fn picture() -> Picture {
+        let cells = Grid::parse("#.\n.#", |symbol| {
+            Some(Cell { symbol, colour: if symbol == '#' { RED } else { BLUE } })
+        })
+        .unwrap();
+        Picture { cells }
+    }
+
+    #[test]
+    fn test_text_and_ansi() {
+        let picture = picture();
+        assert_eq!(picture.to_text(), "#.\n.#");
+        assert_eq!(
+            picture.to_ansi(),
+            "\x1b[38;2;255;0;0m#\x1b[38;2;0;0;255m.\x1b[0m\n\x1b[38;2;0;0;255m.\x1b[38;2;255;0;0m#\x1b[0m\n"
+        );
+    }
+
+    #[test]
+    fn test_gradient() {
+        let stops = [[0, 0, 0], [100, 200, 0], [100, 200, 250]];
+        assert_eq!(gradient(&stops, 0.0), [0, 0, 0]);
+        assert_eq!(gradient(&stops, 0.25), [50, 100, 0]);
+        assert_eq!(gradient(&stops, 0.5), [100, 200, 0]);
+        assert_eq!(gradient(&stops, 1.0), [100, 200, 250]);
+        assert_eq!(gradient(&stops, 7.0), [100, 200, 250]);
+    }
+
+    #[test]
+    fn test_image() {
+        let image = picture().to_image(2);
+        assert_eq!((image.width, image.height), (4, 4));
+        assert_eq!(image.pixels[..4], [RED, RED, BLUE, BLUE]);
+        assert_eq!(image.pixels[12..], [BLUE, BLUE, RED, RED]);
+
+        let mut ppm = vec![];
+        image.write_ppm(&mut ppm).unwrap();
+        assert!(ppm.starts_with(b"P6\n4 4\n255\n"));
+        assert_eq!(ppm.len(), 11 + 4 * 4 * 3);
+    }
+
+    #[test]
+    fn png_has_the_pixels() {
+        let image = picture().to_image(1);
+        let mut png = vec![];
+        image.write_png(&mut png).unwrap();
+
+        assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+        assert_eq!(&png[12..16], b"IHDR");
+        assert_eq!(&png[37..41], b"IDAT");
+        assert!(png.ends_with(&[0, 0, 0, 0, b'I', b'E', b'N', b'D', 0xae, 0x42, 0x60, 0x82]));
+
+        let idat_len = u32::from_be_bytes(png[33..37].try_into().unwrap()) as usize;
+        let mut raw = vec![];
+        flate2::read::ZlibDecoder::new(&png[41..41 + idat_len]).read_to_end(&mut raw).unwrap();
+        assert_eq!(raw, [0, 255, 0, 0, 0, 0, 255, 0, 0, 0, 255, 255, 0, 0]);
+    }
+}
@@ -0,0 +1,20 @@
+//! Random numbers for tests, from a simple generator with a fixed seed, so
+//! that every test run sees the same ones.
+
+/// A linear congruential generator, with the constants from Knuth's MMIX.
+pub struct Random {
+    state: u64,
+}
+
+impl Random {
+    pub fn new(seed: u64) -> Self {
+        Random { state: seed }
+    }
+
+    /// A number in `0..max`.
+    pub fn below(&mut self, max: u64) -> u64 {
+        self.state = self.state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        // the low bits of an LCG repeat quickly, so use the high ones
+        (self.state >> 33) % max
+    }
+}
@@ -0,0 +1,92 @@
+//! Runs every registered solver on the example from its puzzle description and
+//! compares against the published answers in `fixtures/examples/answers.toml`.
+
+use std::{
+    fs,
+    panic::{catch_unwind, AssertUnwindSafe},
+    path::PathBuf,
+};
+
+use crate::{
+    answers::{AnswerSheet, Verdict},
+    solver::{Solver, SOLVERS},
+};
+
+fn examples_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("fixtures/examples")
+}
+
+/// Checks one day against the sheet and returns a description of every problem.
+fn check_solver(solver: &dyn Solver, sheet: &AnswerSheet) -> Vec<String> {
+    let day = solver.day();
+    let input_path = examples_dir().join(format!("day{:02}.txt", day));
+    let Ok(input) = fs::read_to_string(&input_path) else {
+        return vec![format!("Day {}: missing example input {}", day, input_path.display())];
+    };
+
+    let mut problems = vec![];
+    for part in [1, 2] {
+        match catch_unwind(AssertUnwindSafe(|| solver.part(part, &input))) {
+            Ok(Ok(answer)) => match sheet.check(day, part, &answer) {
+                Verdict::Match => {}
+                Verdict::Mismatch(expected) => problems.push(format!(
+                    "Day {} part {}: expected {:?}, got {:?}",
+                    day, part, expected, answer
+                )),
+                Verdict::Unknown => {
+                    problems.push(format!("Day {} part {}: no expected answer", day, part))
+                }
+            },
+            Ok(Err(e)) => problems.push(format!("Day {} part {}: {}", day, part, e)),
+            Err(_) => problems.push(format!("Day {} part {}: panicked", day, part)),
+        }
+    }
+    problems
+}
+
+#[test]
+fn every_solver_matches_its_example() {
+    let sheet = AnswerSheet::load(&examples_dir().join("answers.toml")).unwrap();
+
+    let mut problems = vec![];
+    for solver in SOLVERS.iter() {
+        let day = solver.day();
+        if !sheet.has_day(day) {
+            problems.push(format!("Day {}: no entry [day{:02}] in answers.toml", day, day));
+            continue;
+        }
+
+        if let Some(reason) = sheet.skip_reason(day) {
+            println!("Skipping day {}: {}", day, reason);
+            continue;
+        }
+
+        match sheet.settings(day).map(|settings| solver.with_settings(settings)) {
+            None => problems.extend(check_solver(*solver, &sheet)),
+            Some(Ok(solver)) => problems.extend(check_solver(solver.as_ref(), &sheet)),
+            Some(Err(e)) => problems.push(e.to_string()),
+        }
+    }
+
+    assert!(problems.is_empty(), "\n{}", problems.join("\n"));
+}
+
+#[test]
+fn check_solver_reports_wrong_answers() {
+    let sheet = AnswerSheet::parse("[day01]\npart1 = 24000\npart2 = 42\n").unwrap();
+
+    let problems = check_solver(SOLVERS[0], &sheet);
+    assert_eq!(problems, vec!["Day 1 part 2: expected Int(42), got Int(45000)".to_string()]);
+}
+
+#[test]
+fn malformed_input_is_located_precisely() {
+    let solver = crate::solver::find_solver(4).unwrap();
+    let error = solver.part(1, "2-4,6-8\n2-3,4-x\n").unwrap_err();
+
+    assert!(
+        matches!(error, crate::error::AocError::Parse { day: 4, line: 2, column: 5, .. }),
+        "{:?}",
+        error
+    );
+}
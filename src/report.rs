@@ -0,0 +1,275 @@
+use std::time::Duration;
+
+use itertools::Itertools;
+use json::JsonValue;
+
+use crate::{
+    answers::{AnswerSheet, Verdict},
+    error::AocError,
+    input::InputSource,
+    picture::Picture,
+    solver::{Answer, Run},
+    timing::DayTimings,
+};
+
+/// Everything that happened when running one day, ready to be printed in any
+/// of the output formats.
+#[derive(Debug)]
+pub struct DayReport {
+    pub day: u32,
+    pub title: Option<&'static str>,
+    pub parts: Vec<u8>,
+    pub outcome: Outcome,
+}
+
+#[derive(Debug)]
+pub enum Outcome {
+    /// There's no solver for the day.
+    Unsolved,
+    /// We couldn't get hold of the puzzle input, for this reason.
+    NoInput(String),
+    Failed { source: InputSource, error: AocError },
+    /// One run per benchmark round, all with the same answers. There's only
+    /// a picture if we asked for one and the day can draw.
+    Solved { source: InputSource, runs: Vec<Run>, picture: Option<Picture> },
+}
+
+/// One line of machine-readable output: the result of one part of one day.
+#[derive(Debug, PartialEq)]
+pub struct Record {
+    pub day: u32,
+    pub part: u8,
+    pub title: Option<&'static str>,
+    pub source: Option<String>,
+    pub answer: Option<Answer>,
+    pub verdict: Option<Verdict>,
+    /// Mean times over all runs.
+    pub parse_time: Option<Duration>,
+    pub time: Option<Duration>,
+    pub error: Option<String>,
+}
+
+impl Record {
+    fn status(&self) -> &'static str {
+        match (&self.error, &self.verdict) {
+            (Some(_), _) | (None, None) => "error",
+            (None, Some(Verdict::Match)) => "correct",
+            (None, Some(Verdict::Mismatch(_))) => "wrong",
+            (None, Some(Verdict::Unknown)) => "unchecked",
+        }
+    }
+
+    fn expected(&self) -> Option<&Answer> {
+        match &self.verdict {
+            Some(Verdict::Mismatch(expected)) => Some(expected),
+            _ => None,
+        }
+    }
+}
+
+impl DayReport {
+    /// One record per selected part, with the answers checked against `sheet`.
+    pub fn records(&self, sheet: &AnswerSheet) -> Vec<Record> {
+        let record = |part| Record {
+            day: self.day,
+            part,
+            title: self.title,
+            source: None,
+            answer: None,
+            verdict: None,
+            parse_time: None,
+            time: None,
+            error: None,
+        };
+
+        match &self.outcome {
+            Outcome::Unsolved => self
+                .parts
+                .iter()
+                .map(|&part| Record { error: Some("Not solved yet".to_string()), ..record(part) })
+                .collect(),
+            Outcome::NoInput(reason) => self
+                .parts
+                .iter()
+                .map(|&part| Record { error: Some(reason.clone()), ..record(part) })
+                .collect(),
+            Outcome::Failed { source, error } => self
+                .parts
+                .iter()
+                .map(|&part| Record {
+                    source: Some(source.to_string()),
+                    error: Some(error.to_string()),
+                    ..record(part)
+                })
+                .collect(),
+            Outcome::Solved { source, runs, .. } => {
+                let timings = DayTimings::from_runs(self.day, self.title.unwrap_or(""), runs);
+                runs[0]
+                    .parts
+                    .iter()
+                    .zip(&timings.parts)
+                    .map(|(part_run, (_, stats))| Record {
+                        source: Some(source.to_string()),
+                        answer: Some(part_run.answer.clone()),
+                        verdict: Some(sheet.check(self.day, part_run.part, &part_run.answer)),
+                        parse_time: Some(timings.parse.mean),
+                        time: Some(stats.mean),
+                        ..record(part_run.part)
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+fn answer_to_json(answer: &Answer) -> JsonValue {
+    match answer {
+        Answer::Int(x) => (*x).into(),
+        Answer::Text(s) | Answer::Crt(s) => s.as_str().into(),
+    }
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+/// All records as a JSON array of objects. Times are in milliseconds, and
+/// fields that don't apply are `null`.
+pub fn to_json(records: &[Record]) -> String {
+    let array = records
+        .iter()
+        .map(|r| {
+            let mut object = JsonValue::new_object();
+            object["day"] = r.day.into();
+            object["part"] = r.part.into();
+            object["title"] = r.title.into();
+            object["source"] = r.source.clone().into();
+            object["status"] = r.status().into();
+            object["answer"] = r.answer.as_ref().map_or(JsonValue::Null, answer_to_json);
+            object["expected"] = r.expected().map_or(JsonValue::Null, answer_to_json);
+            object["parse_ms"] = r.parse_time.map(millis).into();
+            object["time_ms"] = r.time.map(millis).into();
+            object["error"] = r.error.clone().into();
+            object
+        })
+        .collect();
+
+    json::stringify_pretty(JsonValue::Array(array), 2)
+}
+
+/// Quotes a CSV field if it has to be. Day 10's answer and parse errors span
+/// several lines, which is fine inside quotes.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn csv_cell(x: Option<impl ToString>) -> String {
+    x.map_or(String::new(), |x| x.to_string())
+}
+
+/// All records as CSV with a header row. Empty fields are ones that don't apply.
+pub fn to_csv(records: &[Record]) -> String {
+    let header = "day,part,title,source,status,answer,expected,parse_ms,time_ms,error";
+    let time = |t: Option<Duration>| t.map(|t| format!("{:.3}", millis(t)));
+    let rows = records.iter().map(|r| {
+        [
+            r.day.to_string(),
+            r.part.to_string(),
+            csv_cell(r.title),
+            csv_cell(r.source.as_ref()),
+            r.status().to_string(),
+            csv_cell(r.answer.as_ref()),
+            csv_cell(r.expected()),
+            csv_cell(time(r.parse_time)),
+            csv_cell(time(r.time)),
+            csv_cell(r.error.as_ref()),
+        ]
+        .iter()
+        .map(|field| csv_field(field))
+        .join(",")
+    });
+
+    std::iter::once(header.to_string()).chain(rows).join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::PartRun;
+
+    fn solved_day() -> DayReport {
+        let part_run = |part, answer: Answer| PartRun { part, answer, time: Duration::from_millis(2) };
+        DayReport {
+            day: 5,
+            title: Some("Supply Stacks"),
+            parts: vec![1, 2],
+            outcome: Outcome::Solved {
+                source: InputSource::Stdin,
+                runs: vec![Run {
+                    parse_time: Duration::from_millis(1),
+                    parts: vec![part_run(1, "CMZ".into()), part_run(2, "MDC".into())],
+                }],
+                picture: None,
+            },
+        }
+    }
+
+    #[test]
+    fn solved_days_have_checked_answers() {
+        let sheet = AnswerSheet::parse("[day05]\npart1 = \"CMZ\"\npart2 = \"MCD\"\n").unwrap();
+        let records = solved_day().records(&sheet);
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].status(), "correct");
+        assert_eq!(records[0].source.as_deref(), Some("stdin"));
+        assert_eq!(records[1].status(), "wrong");
+        assert_eq!(records[1].expected(), Some(&Answer::Text("MCD".to_string())));
+        assert_eq!(records[1].time, Some(Duration::from_millis(2)));
+    }
+
+    #[test]
+    fn unsolved_days_are_an_error_for_every_part() {
+        let report = DayReport {
+            day: 17,
+            title: None,
+            parts: vec![1, 2],
+            outcome: Outcome::Unsolved,
+        };
+        let records = report.records(&AnswerSheet::default());
+
+        assert_eq!(records.len(), 2);
+        assert!(records.iter().all(|r| r.status() == "error" && r.error.is_some()));
+        assert_eq!(records[1].part, 2);
+    }
+
+    #[test]
+    fn test_to_json() {
+        let records = solved_day().records(&AnswerSheet::default());
+        let parsed = json::parse(&to_json(&records)).unwrap();
+
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed[0]["day"], 5);
+        assert_eq!(parsed[0]["answer"], "CMZ");
+        assert_eq!(parsed[0]["status"], "unchecked");
+        assert_eq!(parsed[1]["parse_ms"], 1.0);
+        assert!(parsed[1]["error"].is_null());
+    }
+
+    #[test]
+    fn test_to_csv() {
+        let mut records = solved_day().records(&AnswerSheet::default());
+        records[1].answer = Some(Answer::Crt("#.\n.#".to_string()));
+        records[1].error = Some("say \"hi\", please".to_string());
+
+        let csv = to_csv(&records);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "day,part,title,source,status,answer,expected,parse_ms,time_ms,error");
+        assert_eq!(lines[1], "5,1,Supply Stacks,stdin,unchecked,CMZ,,1.000,2.000,");
+        assert_eq!(lines[2], "5,2,Supply Stacks,stdin,error,\"#.");
+        assert_eq!(lines[3], ".#\",,1.000,2.000,\"say \"\"hi\"\", please\"");
+    }
+}
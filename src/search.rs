@@ -0,0 +1,251 @@
+//! Shortest paths in graphs that are only given by a successor function, so
+//! that grids, valve networks and whatever else the puzzles come up with don't
+//! need to be turned into a graph data structure first.
+//!
+//! All searches can start from several nodes at once; each node then gets the
+//! distance to the closest start. For searching backwards, from the goal to
+//! wherever it can be reached from, pass a function for the predecessors instead
+//! of the successors.
+
+use std::{
+    cmp::Reverse,
+    collections::{HashMap, VecDeque},
+    hash::Hash,
+    ops::Add,
+};
+
+use num::Zero;
+use priority_queue::PriorityQueue;
+
+/// What a search found out: the distance to every node it reached, and how it
+/// got there.
+#[derive(Debug, Clone)]
+pub struct SearchResult<N, C> {
+    distances: HashMap<N, C>,
+    predecessors: HashMap<N, N>,
+    goal: Option<N>,
+}
+
+impl<N: Clone + Eq + Hash, C: Copy> SearchResult<N, C> {
+    fn new() -> Self {
+        SearchResult { distances: HashMap::new(), predecessors: HashMap::new(), goal: None }
+    }
+
+    /// The first node the search reached that was a goal.
+    pub fn goal(&self) -> Option<&N> {
+        self.goal.as_ref()
+    }
+
+    /// The shortest distance from any start to `node`, if the search got there.
+    pub fn distance(&self, node: &N) -> Option<C> {
+        self.distances.get(node).copied()
+    }
+
+    /// Every node the search reached, with its distance.
+    pub fn distances(&self) -> &HashMap<N, C> {
+        &self.distances
+    }
+
+    /// A shortest path from one of the starts to `node`, both included.
+    pub fn path_to(&self, node: &N) -> Option<Vec<N>> {
+        self.distances.get(node)?;
+
+        let mut path = vec![node.clone()];
+        while let Some(previous) = self.predecessors.get(path.last().unwrap()) {
+            path.push(previous.clone());
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+/// Breadth-first search, for graphs where every step counts the same. Stops at
+/// the first node that `is_goal`, or once it has seen every reachable node.
+pub fn bfs<N, I>(
+    starts: impl IntoIterator<Item = N>,
+    mut successors: impl FnMut(&N) -> I,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> SearchResult<N, usize>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut result = SearchResult::new();
+    let mut queue = VecDeque::new();
+    for start in starts {
+        if !result.distances.contains_key(&start) {
+            result.distances.insert(start.clone(), 0);
+            queue.push_back(start);
+        }
+    }
+
+    while let Some(node) = queue.pop_front() {
+        if is_goal(&node) {
+            result.goal = Some(node);
+            break;
+        }
+
+        let distance = result.distances[&node] + 1;
+        for next in successors(&node) {
+            if !result.distances.contains_key(&next) {
+                result.distances.insert(next.clone(), distance);
+                result.predecessors.insert(next.clone(), node.clone());
+                queue.push_back(next);
+            }
+        }
+    }
+
+    result
+}
+
+/// Dijkstra's algorithm, for steps with different non-negative costs.
+/// `successors` gives the next nodes with the cost of getting there.
+pub fn dijkstra<N, C, I>(
+    starts: impl IntoIterator<Item = N>,
+    successors: impl FnMut(&N) -> I,
+    is_goal: impl FnMut(&N) -> bool,
+) -> SearchResult<N, C>
+where
+    N: Clone + Eq + Hash,
+    C: Zero + Ord + Copy,
+    I: IntoIterator<Item = (N, C)>,
+{
+    astar(starts, successors, |_| C::zero(), is_goal)
+}
+
+/// A*, which is Dijkstra's algorithm heading for the goal first. The
+/// `heuristic` must never overestimate the distance left to the closest goal,
+/// nor drop by more than the cost of a step.
+///
+/// Distances are only certain for nodes the search settled on, so if it stops
+/// at a goal, `distances` doesn't have everything in the graph.
+pub fn astar<N, C, I>(
+    starts: impl IntoIterator<Item = N>,
+    mut successors: impl FnMut(&N) -> I,
+    mut heuristic: impl FnMut(&N) -> C,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> SearchResult<N, C>
+where
+    N: Clone + Eq + Hash,
+    C: Zero + Ord + Copy + Add<Output = C>,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let mut result = SearchResult::new();
+    // nodes we've seen but not settled yet, with the best distance so far and where it came from
+    let mut frontier: HashMap<N, (C, Option<N>)> = HashMap::new();
+    let mut queue = PriorityQueue::new();
+
+    for start in starts {
+        queue.push(start.clone(), Reverse(heuristic(&start)));
+        frontier.insert(start, (C::zero(), None));
+    }
+
+    while let Some((node, _)) = queue.pop() {
+        let (distance, previous) = frontier.remove(&node).unwrap();
+        result.distances.insert(node.clone(), distance);
+        if let Some(previous) = previous {
+            result.predecessors.insert(node.clone(), previous);
+        }
+
+        if is_goal(&node) {
+            result.goal = Some(node);
+            break;
+        }
+
+        for (next, cost) in successors(&node) {
+            if result.distances.contains_key(&next) {
+                continue;
+            }
+            let next_distance = distance + cost;
+            if frontier.get(&next).is_none_or(|(d, _)| next_distance < *d) {
+                queue.push_increase(next.clone(), Reverse(next_distance + heuristic(&next)));
+                frontier.insert(next, (next_distance, Some(node.clone())));
+            }
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::Vec2;
+    use crate::grid::{Grid, GridView};
+    use indoc::indoc;
+
+    const MAZE: &str = indoc! {"
+        S..#....
+        .#.#.##.
+        .#...#..
+        .####.#.
+        ......#E"};
+
+    fn maze() -> Grid<bool> {
+        Grid::parse(MAZE, |c| Some(c != '#')).unwrap()
+    }
+
+    fn open_neighbours(grid: &Grid<bool>) -> impl FnMut(&Vec2) -> Vec<Vec2> + '_ {
+        |&pos| grid.neighbours4(pos).filter(|n| grid[*n]).collect()
+    }
+
+    #[test]
+    fn test_bfs() {
+        let grid = maze();
+        let (start, goal) = (Vec2::new(0, 0), Vec2::new(7, 4));
+
+        let result = bfs([start], open_neighbours(&grid), |&pos| pos == goal);
+        assert_eq!(result.goal(), Some(&goal));
+        assert_eq!(result.distance(&goal), Some(15));
+
+        let path = result.path_to(&goal).unwrap();
+        assert_eq!(path.len(), 16);
+        assert_eq!((path[0], path[15]), (start, goal));
+        assert!(path.windows(2).all(|step| step[0].manhattan(&step[1]) == 1));
+
+        // without a goal, it goes everywhere
+        let result = bfs([start], open_neighbours(&grid), |_| false);
+        assert_eq!(result.goal(), None);
+        assert_eq!(result.distances().len(), grid.iter().filter(|(_, open)| **open).count());
+    }
+
+    #[test]
+    fn multiple_starts_find_the_closest() {
+        let grid = maze();
+        let starts = [Vec2::new(0, 0), Vec2::new(7, 0)];
+
+        let result = bfs(starts, open_neighbours(&grid), |_| false);
+        assert_eq!(result.distance(&Vec2::new(4, 0)), Some(3));
+        assert_eq!(result.distance(&Vec2::new(2, 0)), Some(2));
+        assert_eq!(result.path_to(&Vec2::new(4, 0)).unwrap()[0], Vec2::new(7, 0));
+    }
+
+    #[test]
+    fn dijkstra_and_astar_agree() {
+        // stepping onto a digit costs that much
+        let grid = Grid::parse("1163\n1381\n2136", |c| c.to_digit(10)).unwrap();
+        let (start, goal) = (Vec2::new(0, 0), Vec2::new(3, 2));
+        let successors = |&pos: &Vec2| grid.neighbours4(pos).map(|n| (n, grid[n])).collect::<Vec<_>>();
+
+        let result = dijkstra([start], successors, |&pos| pos == goal);
+        assert_eq!(result.distance(&goal), Some(13));
+
+        let heuristic = |pos: &Vec2| pos.manhattan(&goal) as u32;
+        let result = astar([start], successors, heuristic, |&pos| pos == goal);
+        assert_eq!(result.distance(&goal), Some(13));
+        assert_eq!(result.path_to(&goal).unwrap().len(), 6);
+    }
+
+    #[test]
+    fn searching_backwards() {
+        // a one-way street: from n you can go to n + 1 and n + 3
+        let successors = |&n: &u32| [n + 1, n + 3].into_iter().filter(|&n| n <= 10);
+        let predecessors = |&n: &u32| [n.checked_sub(1), n.checked_sub(3)].into_iter().flatten();
+
+        let forward = bfs([0], successors, |&n| n == 10);
+        let backward = bfs([10], predecessors, |_| false);
+        assert_eq!(forward.distance(&10), Some(4));
+        assert_eq!(backward.distance(&0), Some(4));
+        assert_eq!(backward.distance(&9), Some(1));
+    }
+}
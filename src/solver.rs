@@ -0,0 +1,240 @@
+use crate::{
+    day01::Day01, day02::Day02, day03::Day03, day04::Day04, day05::Day05, day06::Day06,
+    day07::Day07, day08::Day08, day09::Day09, day10::Day10, day11::Day11, day12::Day12,
+    day13::Day13, day14::Day14, day15::Day15, day16::Day16, day20::Day20,
+    error::{AocError, ParseError},
+    picture::Picture,
+};
+
+use toml::Table;
+
+use simple_error::SimpleError;
+use std::{
+    fmt::Display,
+    time::{Duration, Instant},
+};
+
+/// The answer to one part of a day's puzzle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Int(i64),
+    Text(String),
+    /// Pixels drawn on the CRT of day 10. A human has to read the letters off it.
+    Crt(String),
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Answer::Int(x) => write!(f, "{}", x),
+            Answer::Text(s) | Answer::Crt(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+macro_rules! impl_answer_from_int {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(x: $t) -> Self {
+                    Answer::Int(x.try_into().expect("Answer doesn't fit into an i64"))
+                }
+            }
+        )*
+    };
+}
+
+impl_answer_from_int!(i32, i64, u32, u64, usize);
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Answer::Text(s)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Self {
+        Answer::Text(s.to_string())
+    }
+}
+
+/// A solution for one day of the advent calendar.
+///
+/// The input is parsed once and both parts work on the parsed form, so that
+/// parsing and solving can be timed separately. Implementations are stateless
+/// unit structs; they get a `Solver` implementation for free, which is what the
+/// `SOLVERS` registry holds.
+///
+/// Malformed input is a `ParseError`, pointing at where in the input it went
+/// wrong. A part that can't find an answer for a well-formed input says why.
+pub trait Puzzle: Sync {
+    type Parsed;
+
+    fn day(&self) -> u32;
+    fn title(&self) -> &'static str;
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Parsed, ParseError<'a>>;
+    fn part1(&self, parsed: &Self::Parsed) -> Result<Answer, SimpleError>;
+    fn part2(&self, parsed: &Self::Parsed) -> Result<Answer, SimpleError>;
+
+    /// A picture of the puzzle and its solution, for the days that can draw one.
+    fn draw(&self, _parsed: &Self::Parsed, _options: &DrawOptions) -> Option<Picture> {
+        None
+    }
+
+    /// Shows the puzzle being solved, passing every picture along to `frame`.
+    /// Returns whether the day has an animation at all.
+    fn animate(&self, _parsed: &Self::Parsed, _options: &DrawOptions, _frame: &mut dyn FnMut(&Picture)) -> bool {
+        false
+    }
+
+    /// The puzzle with some of its numbers changed, for the days where more
+    /// than the input decides the answers. Most days don't have any settings.
+    fn with_settings(&self, _settings: &Table) -> Result<Self, String>
+    where
+        Self: Sized,
+    {
+        Err("This day doesn't have any settings".to_string())
+    }
+}
+
+/// What the pictures show, besides the puzzle. Unlike settings, these never
+/// change the answers, so they come from the command line.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DrawOptions {
+    /// Day 14 shows the cave with the floor of part 2.
+    pub floor: bool,
+}
+
+/// The answers of one run of a day, with the time each step took.
+#[derive(Debug, Clone)]
+pub struct Run {
+    pub parse_time: Duration,
+    pub parts: Vec<PartRun>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PartRun {
+    pub part: u8,
+    pub answer: Answer,
+    pub time: Duration,
+}
+
+/// A day of the advent calendar, with the type of its parsed input erased so
+/// that all days fit into one registry.
+pub trait Solver: Sync {
+    fn day(&self) -> u32;
+    fn title(&self) -> &'static str;
+
+    /// Parses the input once and solves the given `parts` on it.
+    fn run(&self, input: &str, parts: &[u8]) -> Result<Run, AocError>;
+
+    fn part(&self, part: u8, input: &str) -> Result<Answer, AocError> {
+        Ok(self.run(input, &[part])?.parts.remove(0).answer)
+    }
+
+    fn solve(&self, input: &str) -> Result<(Answer, Answer), AocError> {
+        let mut parts = self.run(input, &[1, 2])?.parts.into_iter().map(|run| run.answer);
+        Ok((parts.next().unwrap(), parts.next().unwrap()))
+    }
+
+    fn draw(&self, input: &str, options: &DrawOptions) -> Result<Option<Picture>, AocError>;
+    fn animate(&self, input: &str, options: &DrawOptions, frame: &mut dyn FnMut(&Picture)) -> Result<bool, AocError>;
+
+    /// The same day with the given `settings`, which come from the answer sheet.
+    fn with_settings(&self, settings: &Table) -> Result<Box<dyn Solver>, AocError>;
+}
+
+impl<P: Puzzle + 'static> Solver for P {
+    fn day(&self) -> u32 {
+        Puzzle::day(self)
+    }
+
+    fn title(&self) -> &'static str {
+        Puzzle::title(self)
+    }
+
+    fn run(&self, input: &str, parts: &[u8]) -> Result<Run, AocError> {
+        let day = Puzzle::day(self);
+
+        let start = Instant::now();
+        let parsed = self.parse(input).map_err(|e| AocError::parse(day, input, e))?;
+        let parse_time = start.elapsed();
+
+        let parts = parts
+            .iter()
+            .map(|&part| {
+                let start = Instant::now();
+                let answer = match part {
+                    1 => self.part1(&parsed),
+                    2 => self.part2(&parsed),
+                    _ => panic!("There's only two parts per day, not {}", part),
+                };
+                let answer = answer.map_err(|e| AocError::NoAnswer {
+                    day,
+                    part,
+                    reason: e.as_str().to_string(),
+                })?;
+                Ok(PartRun { part, answer, time: start.elapsed() })
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Run { parse_time, parts })
+    }
+
+    fn draw(&self, input: &str, options: &DrawOptions) -> Result<Option<Picture>, AocError> {
+        let parsed = self.parse(input).map_err(|e| AocError::parse(Puzzle::day(self), input, e))?;
+        Ok(Puzzle::draw(self, &parsed, options))
+    }
+
+    fn animate(&self, input: &str, options: &DrawOptions, frame: &mut dyn FnMut(&Picture)) -> Result<bool, AocError> {
+        let parsed = self.parse(input).map_err(|e| AocError::parse(Puzzle::day(self), input, e))?;
+        Ok(Puzzle::animate(self, &parsed, options, frame))
+    }
+
+    fn with_settings(&self, settings: &Table) -> Result<Box<dyn Solver>, AocError> {
+        match Puzzle::with_settings(self, settings) {
+            Ok(puzzle) => Ok(Box::new(puzzle)),
+            Err(reason) => Err(AocError::Settings { day: Puzzle::day(self), reason }),
+        }
+    }
+}
+
+/// Every solved day, in order. To add a new day, add its solver here.
+pub static SOLVERS: [&dyn Solver; 17] = [
+    &Day01, &Day02, &Day03, &Day04, &Day05, &Day06, &Day07, &Day08, &Day09, &Day10, &Day11,
+    &Day12::PUZZLE, &Day13, &Day14, &Day15::PUZZLE, &Day16, &Day20,
+];
+
+pub fn find_solver(day: u32) -> Option<&'static dyn Solver> {
+    SOLVERS.iter().find(|solver| solver.day() == day).copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registry_is_ordered_and_unique() {
+        let days: Vec<u32> = SOLVERS.iter().map(|solver| solver.day()).collect();
+        let mut sorted = days.clone();
+        sorted.sort();
+        sorted.dedup();
+        assert_eq!(days, sorted);
+    }
+
+    #[test]
+    fn test_answer_conversions() {
+        assert_eq!(Answer::from(42usize), Answer::Int(42));
+        assert_eq!(Answer::from(-3i32), Answer::Int(-3));
+        assert_eq!(Answer::from("CMZ"), Answer::Text("CMZ".to_string()));
+
+        assert_eq!(Answer::Int(24933642).to_string(), "24933642");
+        assert_eq!(Answer::Crt("#..#\n.##.".to_string()).to_string(), "#..#\n.##.");
+    }
+
+    #[test]
+    fn finds_solvers_by_day() {
+        assert_eq!(find_solver(14).map(|solver| solver.title()), Some("Regolith Reservoir"));
+        assert!(find_solver(17).is_none());
+    }
+}
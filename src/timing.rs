@@ -0,0 +1,190 @@
+use std::{fmt::Display, time::Duration};
+
+use crate::solver::Run;
+
+/// Summary of the times one step took over a number of runs.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    pub runs: usize,
+    pub mean: Duration,
+    pub min: Duration,
+    pub stddev: Duration,
+}
+
+impl Stats {
+    pub fn from_samples(samples: &[Duration]) -> Self {
+        assert!(!samples.is_empty(), "Need at least one sample for stats");
+
+        let runs = samples.len();
+        let secs: Vec<f64> = samples.iter().map(Duration::as_secs_f64).collect();
+        let mean = secs.iter().sum::<f64>() / runs as f64;
+        let variance = secs.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / runs as f64;
+
+        Stats {
+            runs,
+            mean: Duration::from_secs_f64(mean),
+            min: *samples.iter().min().unwrap(),
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+        }
+    }
+}
+
+impl Display for Stats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.runs == 1 {
+            write!(f, "{:.2?}", self.mean)
+        } else {
+            write!(f, "{:.2?} ± {:.2?} (min {:.2?})", self.mean, self.stddev, self.min)
+        }
+    }
+}
+
+/// How long parsing and each part of a day took.
+#[derive(Debug)]
+pub struct DayTimings {
+    pub day: u32,
+    pub title: &'static str,
+    pub parse: Stats,
+    pub parts: Vec<(u8, Stats)>,
+}
+
+impl DayTimings {
+    /// Collects the timings of repeated `runs` of the same day and parts.
+    pub fn from_runs(day: u32, title: &'static str, runs: &[Run]) -> Self {
+        let parse_times: Vec<Duration> = runs.iter().map(|run| run.parse_time).collect();
+
+        let parts = runs[0]
+            .parts
+            .iter()
+            .enumerate()
+            .map(|(i, part_run)| {
+                let times: Vec<Duration> = runs.iter().map(|run| run.parts[i].time).collect();
+                (part_run.part, Stats::from_samples(&times))
+            })
+            .collect();
+
+        DayTimings { day, title, parse: Stats::from_samples(&parse_times), parts }
+    }
+
+    fn part(&self, part: u8) -> Option<&Stats> {
+        self.parts.iter().find(|(p, _)| *p == part).map(|(_, stats)| stats)
+    }
+
+    fn total(&self) -> Duration {
+        self.parse.mean + self.parts.iter().map(|(_, stats)| stats.mean).sum::<Duration>()
+    }
+}
+
+/// Renders the timings as a table with a row per day and a grand total.
+pub fn summary_table(timings: &[DayTimings]) -> String {
+    let header = ["Day", "Title", "Parse", "Part 1", "Part 2", "Total"].map(String::from);
+
+    let mut rows = vec![header];
+    for t in timings {
+        let part_cell = |part| t.part(part).map_or("-".to_string(), Stats::to_string);
+        rows.push([
+            t.day.to_string(),
+            t.title.to_string(),
+            t.parse.to_string(),
+            part_cell(1),
+            part_cell(2),
+            format!("{:.2?}", t.total()),
+        ]);
+    }
+    let total: Duration = timings.iter().map(DayTimings::total).sum();
+    rows.push([
+        String::new(),
+        "All days".to_string(),
+        String::new(),
+        String::new(),
+        String::new(),
+        format!("{:.2?}", total),
+    ]);
+
+    let widths: Vec<usize> = (0..6)
+        .map(|col| rows.iter().map(|row| row[col].chars().count()).max().unwrap())
+        .collect();
+
+    rows.iter()
+        .map(|row| {
+            row.iter()
+                .zip(&widths)
+                .enumerate()
+                // the title is the only column that reads better left-aligned
+                .map(|(col, (cell, width))| match col {
+                    1 => format!("{:<width$}", cell, width = width),
+                    _ => format!("{:>width$}", cell, width = width),
+                })
+                .collect::<Vec<_>>()
+                .join("  ")
+                .trim_end()
+                .to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::{Answer, PartRun};
+
+    fn ms(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    /// Going through f64 seconds may be off by a few nanoseconds.
+    fn assert_close(actual: Duration, expected: Duration) {
+        assert!(actual.abs_diff(expected) < Duration::from_micros(1), "{:?} != {:?}", actual, expected);
+    }
+
+    fn run(parse: u64, part_times: &[(u8, u64)]) -> Run {
+        Run {
+            parse_time: ms(parse),
+            parts: part_times
+                .iter()
+                .map(|&(part, time)| PartRun { part, answer: Answer::Int(0), time: ms(time) })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_stats() {
+        let stats = Stats::from_samples(&[ms(2), ms(4), ms(4), ms(4), ms(5), ms(5), ms(7), ms(9)]);
+        assert_eq!(stats.runs, 8);
+        assert_eq!(stats.min, ms(2));
+        assert_close(stats.mean, ms(5));
+        assert_close(stats.stddev, ms(2));
+
+        let stats = Stats::from_samples(&[ms(3)]);
+        assert_eq!(stats.stddev, Duration::ZERO);
+        assert_eq!(stats.to_string(), "3.00ms");
+    }
+
+    #[test]
+    fn collects_timings_per_part() {
+        let runs = [run(1, &[(2, 10)]), run(3, &[(2, 20)])];
+        let timings = DayTimings::from_runs(7, "No Space Left On Device", &runs);
+
+        assert_close(timings.parse.mean, ms(2));
+        assert!(timings.part(1).is_none());
+        assert_close(timings.part(2).unwrap().mean, ms(15));
+        assert_close(timings.total(), ms(17));
+    }
+
+    #[test]
+    fn test_summary_table() {
+        let timings = [
+            DayTimings::from_runs(1, "Calorie Counting", &[run(1, &[(1, 2), (2, 3)])]),
+            DayTimings::from_runs(12, "Hill Climbing Algorithm", &[run(4, &[(1, 5)])]),
+        ];
+
+        let table = summary_table(&timings);
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].starts_with("Day  Title"), "{}", table);
+        assert!(lines[1].contains("Calorie Counting ") && lines[1].ends_with("6.00ms"), "{}", table);
+        assert!(lines[2].contains("   -  "), "{}", table);
+        assert!(lines[3].ends_with("15.00ms"), "{}", table);
+    }
+}